@@ -68,10 +68,10 @@ class GameSelector {
     try {
       // Load the Rust WASM module
       const wasm = await import('./rust-game/pkg/rust_game.js');
-      await wasm.default();
+      const exports = await wasm.default();
       
       // Create Rust game wrapper
-      this.currentGame = new RustGameWrapper(wasm);
+      this.currentGame = new RustGameWrapper(wasm, exports.memory);
       await this.currentGame.loadAllImages();
       this.currentGame.start();
     } catch (error) {
@@ -82,9 +82,24 @@ class GameSelector {
   }
 }
 
+const SNAPSHOT_KEY = 'badSkyCab.snapshot';
+
+// Render buffer layout, mirrors rust-game/src/render_buffer.rs
+const RENDER_STRIDE = 9;
+const FLAG_FLIP_X = 1;
+const FLAG_GLOW = 2; // color is the glow colour, for sprites and text
+const FLAG_TEXT = 4;
+const FLAG_FILL = 8;
+const FLAG_CENTERED = 16;
+const FLAG_OUTLINED = 32;
+
+const toCssColor = (color) => `#${color.toString(16).padStart(6, '0')}`;
+
 class RustGameWrapper {
-  constructor(wasmModule) {
+  constructor(wasmModule, memory) {
     this.wasm = wasmModule;
+    this.memory = memory;
+    this.textDecoder = new TextDecoder();
     this.canvas = document.getElementById('game-world');
     this.ctx = this.canvas.getContext('2d');
     this.gameState = null;
@@ -95,142 +110,33 @@ class RustGameWrapper {
         src: './assets/audio/317363.mp3',
         loopStatus: true
     });
+    this.bumpSound = new Sound({
+        src: './assets/audio/2791__bump.wav',
+        loopStatus: false
+    });
+    this.boomSound = new Sound({
+        src: './assets/audio/276341__boom.wav',
+        loopStatus: false
+    });
+    this.flashFrames = 0;
     
-    // Load vehicle images
-    this.vehicleImages = {};
-    
-    // Load orb image
-    this.orbImage = new Image();
-    this.orbImage.src = './assets/images/vehicles/boom.png';
-    
-    // Load player image (yellow taxi)
-    this.playerImage = new Image();
-    this.playerImage.src = './assets/images/vehicles/taxi.png';
-    
-    // Load billboard images
-    this.billboardImages = {};
-    
-    // Load building and platform images
-    this.buildingImages = {};
-
-    // Load obstacle images
-    this.obstacleImages = {};
+    // Images indexed by the sprite ids Rust puts in the render buffer
+    this.spriteKeys = this.wasm.GameState.render_sprite_keys();
+    this.sprites = [];
     
     this.bindEvents();
   }
 
-  async loadAllImages() {
-    await Promise.all([
-        this.loadVehicleImages(),
-        this.loadBillboardImages(),
-        this.loadBuildingImages(),
-        this.loadObstacleImages(),
-    ]);
-  }
-
-  loadVehicleImages() {
-    const imageNames = {
-      'Taxi_right': './assets/images/vehicles/taxi.png',
-      'Taxi_left': './assets/images/vehicles/taxi_left.png',
-      'Police_right': './assets/images/vehicles/police.png',
-      'Police_left': './assets/images/vehicles/police_left.png',
-      'Civil_right': './assets/images/vehicles/civil.png',
-      'Civil_left': './assets/images/vehicles/civil_left.png',
-      'Delivery_right': './assets/images/vehicles/delivery_truck.png',
-      'Delivery_left': './assets/images/vehicles/delivery_left.png',
-      'Train_front': './assets/images/vehicles/train_front.png',
-      'Train_center': './assets/images/vehicles/train_center.png',
-      'Train_back': './assets/images/vehicles/train_back.png'
-    };
-
-    const promises = Object.entries(imageNames).map(([key, src]) => {
-        return new Promise((resolve, reject) => {
-            const img = new Image();
-            img.onload = () => {
-                this.vehicleImages[key] = img;
-                resolve();
-            };
-            img.onerror = reject;
-            img.src = src;
-        });
-    });
-
-    return Promise.all(promises);
-  }
-
-  loadBillboardImages() {
-    const billboardNames = {
-      'FirstBreak': './assets/images/billboards/first_break.png',
-      'SecondBreak': './assets/images/billboards/second_break.png',
-      'Security': './assets/images/billboards/security.png',
-      'Security2': './assets/images/billboards/security2.png',
-      'SharkMovie': './assets/images/billboards/shark_movie.png'
-    };
-
-    const promises = Object.entries(billboardNames).map(([key, src]) => {
-        return new Promise((resolve, reject) => {
-            const img = new Image();
-            img.onload = () => {
-                this.billboardImages[key] = img;
-                resolve();
-            };
-            img.onerror = reject;
-            img.src = src;
-        });
-    });
-
-    return Promise.all(promises);
-  }
-
-  loadBuildingImages() {
-    const buildingNames = {
-      // Buildings
-      'TallTower': './assets/images/buildings/tall_building.png',
-      'WideTower': './assets/images/buildings/wide_building.png',
-      'BuildingTop': './assets/images/buildings/wide_building.png', // Use wide building for building tops
-      
-      // Platforms
-      'FloatingPlatform': './assets/images/platforms/sky_condo.png',
-      
-      // Additional platforms we can use
-      'ClosedShop': './assets/images/platforms/Closed-Shop-Single.png',
-      'ChipperShop': './assets/images/platforms/chipper_shop.png',
-      'SushiBar': './assets/images/platforms/sushi_bar.png',
-      'MoodySkyCondo': './assets/images/platforms/moody_sky_condo.png',
-      'Drone': './assets/images/platforms/drone.png'
-    };
-
-    const promises = Object.entries(buildingNames).map(([key, src]) => {
-        return new Promise((resolve, reject) => {
-            const img = new Image();
-            img.onload = () => {
-                this.buildingImages[key] = img;
-                resolve();
-            };
-            img.onerror = reject;
-            img.src = src;
-        });
-    });
-
-    return Promise.all(promises);
-  }
-
-  loadObstacleImages() {
-    const obstacleImageNames = {
-        'WideTower': './assets/images/buildings/wide_building.png',
-        'TallTower': './assets/images/buildings/tall_building.png',
-        'BuildingTop': './assets/images/buildings/tall_building.png',
-    };
-
-    const promises = Object.entries(obstacleImageNames).map(([key, src]) => {
+  loadAllImages() {
+    const promises = this.spriteKeys.map((key, id) => {
         return new Promise((resolve, reject) => {
             const img = new Image();
             img.onload = () => {
-                this.obstacleImages[key] = img;
+                this.sprites[id] = img;
                 resolve();
             };
             img.onerror = reject;
-            img.src = src;
+            img.src = `./assets/images/${key}.png`;
         });
     });
 
@@ -239,8 +145,13 @@ class RustGameWrapper {
 
   start() {
     this.gameState = new this.wasm.GameState();
+    // Record every run so a replay can be attached to bug reports. A resumed
+    // session brings back the recording it was saved with.
+    this.gameState.start_recording();
+    this.resumeSnapshot();
     this.running = true;
-    this.gameLoop();
+    this.lastFrameTime = null;
+    requestAnimationFrame((time) => this.gameLoop(time));
   }
 
   bindEvents() {
@@ -248,6 +159,19 @@ class RustGameWrapper {
     document.addEventListener('keydown', (e) => this.handleInput(e, true));
     document.addEventListener('keyup', (e) => this.handleInput(e, false));
     this.canvas.addEventListener('mouseup', (e) => this.handleMouseUp(e));
+
+    // Closing or reloading the tab never reaches cleanup(), so save whenever
+    // the page is hidden and drop the save once it's back in front
+    this.onVisibilityChange = () => {
+      if (document.visibilityState === 'hidden') {
+        this.suspendSnapshot();
+      } else {
+        localStorage.removeItem(SNAPSHOT_KEY);
+      }
+    };
+    this.onPageHide = () => this.suspendSnapshot();
+    document.addEventListener('visibilitychange', this.onVisibilityChange);
+    window.addEventListener('pagehide', this.onPageHide);
   }
 
   handleMouseUp(e) {
@@ -285,387 +209,199 @@ class RustGameWrapper {
     if (pressed && this.gameState.is_game_over()) {
       if (key === 'r' || key === 'R') {
         this.gameState.reset();
+        // A session resumed from an unrecorded snapshot starts recording again here
+        if (!this.gameState.is_recording()) {
+          this.gameState.start_recording();
+        }
         return;
       } else if (key === 'Escape') {
         // Go back to menu
         window.dispatchEvent(new Event('backToMenu'));
         return;
+      } else if ((key === 'b' || key === 'B') && this.gameState.is_recording()) {
+        this.downloadReplay();
+        return;
       }
     }
     
     // Regular movement controls
+    const { Action, InputSource } = this.wasm;
     if (key === 'ArrowRight' || key === 'd') {
-      this.gameState.set_input('right', pressed);
+      this.gameState.set_action(InputSource.Keyboard, Action.Right, pressed);
     } else if (key === 'ArrowLeft' || key === 'a') {
-      this.gameState.set_input('left', pressed);
+      this.gameState.set_action(InputSource.Keyboard, Action.Left, pressed);
     } else if (key === 'ArrowUp' || key === 'w') {
-      this.gameState.set_input('up', pressed);
+      this.gameState.set_action(InputSource.Keyboard, Action.Up, pressed);
     } else if (key === 'ArrowDown' || key === 's') {
-      this.gameState.set_input('down', pressed);
+      this.gameState.set_action(InputSource.Keyboard, Action.Down, pressed);
     }
   }
 
-  gameLoop() {
-    if (!this.running) return;
-    
-    // Update game state in Rust
-    this.gameState.update();
-    
-    // Get serialized state from Rust
-    const state = JSON.parse(this.gameState.get_state());
-    
-    // Render using JavaScript
-    this.render(state);
-    
-    requestAnimationFrame(() => this.gameLoop());
+  // Left stick of the first connected gamepad gives analog thrust; Rust
+  // merges it with the keyboard and ignores repeats of the same value
+  pollGamepad() {
+    const gamepad = navigator.getGamepads ? navigator.getGamepads()[0] : null;
+    if (!gamepad) return;
+    const { Axis, InputSource } = this.wasm;
+    const deadZone = (value) => (Math.abs(value) < 0.15 ? 0 : value);
+    this.gameState.set_axis(InputSource.Gamepad, Axis.Horizontal, deadZone(gamepad.axes[0]));
+    this.gameState.set_axis(InputSource.Gamepad, Axis.Vertical, deadZone(gamepad.axes[1]));
   }
 
-  render(state) {
-    this.ctx.clearRect(0, 0, this.canvas.width, this.canvas.height);
-    
-    // Render distant background buildings (furthest back)
-    if (state.background?.distant_buildings) {
-      this.ctx.save();
-      this.ctx.globalAlpha = 0.75; // 75% opacity for distant background
-      state.background.distant_buildings.forEach((building, index) => {
-        this.renderBackgroundBuilding(building, state.background.distant_building_offset || 0);
-      });
-      this.ctx.restore();
-    }
-
-    // Render far background buildings (parallax)
-    if (state.background?.far_buildings) {
-      this.ctx.save();
-      this.ctx.globalAlpha = 0.75; // 75% opacity for near background
-      state.background.far_buildings.forEach((building, index) => {
-        this.renderBackgroundBuilding(building, state.background.far_building_offset || 0);
-      });
-      this.ctx.restore();
-    }
-
-    // Render traffic lanes with vehicles FIRST (background layer)
-    if (state.background?.traffic_vehicles) {
-      state.background.traffic_vehicles.forEach(vehicle => {
-        this.renderVehicle(vehicle);
-      });
-      this.ctx.globalAlpha = 1.0;
-      this.ctx.shadowBlur = 0;
-    }
-
-    // Render billboards AFTER traffic (foreground layer)
-    if (state.background?.billboards) {
-      state.background.billboards.forEach(billboard => {
-        this.renderBillboard(billboard);
-      });
-    }
-
-    // Render game obstacles (foreground)
-    if (state.obstacles) {
-      state.obstacles.forEach(obstacle => {
-        this.renderObstacle(obstacle);
-      });
-    }
-    
-    // Render player (always on top) - use taxi image
-    if (state.player) {
-      if (this.playerImage && this.playerImage.complete) {
-        this.ctx.drawImage(this.playerImage, state.player.x, state.player.y, state.player.width, state.player.height);
-      } else {
-        // Fallback to yellow rectangle if image not loaded
-        this.ctx.fillStyle = '#ffff00';
-        this.ctx.fillRect(state.player.x, state.player.y, state.player.width, state.player.height);
-        
-        // Add player border
-        this.ctx.strokeStyle = '#ff6600';
-        this.ctx.lineWidth = 2;
-        this.ctx.strokeRect(state.player.x, state.player.y, state.player.width, state.player.height);
-      }
-      
-    }
-    
-    // Render UI
-    this.ctx.fillStyle = 'white';
-    this.ctx.font = '20px Arial';
-    this.ctx.strokeStyle = 'black';
-    this.ctx.lineWidth = 3;
-    
-    // Score with outline for readability
-    const scoreText = `Score: ${state.score || 0}`;
-    this.ctx.strokeText(scoreText, 10, 30);
-    this.ctx.fillText(scoreText, 10, 30);
-    
-    // Color-coded damage indicator like original game
-    const damage = state.player?.damage || 0;
-    const structuralIntegrity = Math.floor(10 - damage);
-    
-    // Structural integrity label
-    this.ctx.strokeText('Structural Integrity:', 10, 60);
-    this.ctx.fillText('Structural Integrity:', 10, 60);
-    
-    // Color-coded damage value
-    let damageColor;
-    if (damage < 2) {
-      damageColor = '#00ff00'; // Green - healthy
-    } else if (damage < 6) {
-      damageColor = '#daa520'; // Goldenrod - damaged  
-    } else {
-      damageColor = '#ff0000'; // Red - critical
-    }
-    
-    this.ctx.strokeStyle = damageColor;
-    this.ctx.fillStyle = damageColor;
-    this.ctx.strokeText(`${structuralIntegrity}`, 200, 60);
-    this.ctx.fillText(`${structuralIntegrity}`, 200, 60);
-    
-    // Reset colors for other UI elements
-    this.ctx.strokeStyle = 'black';
-    this.ctx.fillStyle = 'white';
-    
-    // Game over overlay
-    if (state.game_over) {
-      this.ctx.save();
-      this.ctx.fillStyle = 'rgba(0, 0, 0, 0.8)';
-      this.ctx.fillRect(0, 0, this.canvas.width, this.canvas.height);
-      
-      this.ctx.fillStyle = '#ff0066';
-      this.ctx.font = '48px Arial';
-      this.ctx.textAlign = 'center';
-      const gameOverText = 'GAME OVER';
-      this.ctx.strokeText(gameOverText, this.canvas.width/2, this.canvas.height/2);
-      this.ctx.fillText(gameOverText, this.canvas.width/2, this.canvas.height/2);
-      
-      this.ctx.fillStyle = 'white';
-      this.ctx.font = '24px Arial';
-      const finalScoreText = `Final Score: ${state.score || 0}`;
-      this.ctx.strokeText(finalScoreText, this.canvas.width/2, this.canvas.height/2 + 50);
-      this.ctx.fillText(finalScoreText, this.canvas.width/2, this.canvas.height/2 + 50);
-      
-      this.ctx.font = '18px Arial';
-      const restartText = 'Press R to restart or ESC for menu';
-      this.ctx.strokeText(restartText, this.canvas.width/2, this.canvas.height/2 + 80);
-      this.ctx.fillText(restartText, this.canvas.width/2, this.canvas.height/2 + 80);
-      
-      this.ctx.restore();
+  downloadReplay() {
+    let bytes;
+    try {
+      bytes = this.gameState.export_replay();
+    } catch (error) {
+      console.warn('No replay to save:', error);
+      return;
     }
-    this.speakerIcon.draw(this.ctx, this.soundPaused);
-    this.themeSound.play(this.soundPaused);
+    const blob = new Blob([bytes], { type: 'application/octet-stream' });
+    const link = document.createElement('a');
+    link.href = URL.createObjectURL(blob);
+    link.download = `bad-sky-cab-${this.gameState.get_seed()}.bscr`;
+    link.click();
+    URL.revokeObjectURL(link.href);
   }
 
-  renderVehicle(vehicle) {
-    if (vehicle.vehicle_type === 'Train') {
-      this.renderTrain(vehicle);
-    } else if (vehicle.vehicle_type === 'TrafficOrb') {
-      this.renderOrb(vehicle);
-    } else {
-      this.renderRegularVehicle(vehicle);
-    }
-    
+  gameLoop(time) {
+    if (!this.running) return;
     
-    // Add status indicators
-    if (vehicle.avoiding) {
-      this.ctx.fillStyle = 'yellow';
-      this.ctx.font = '14px Arial';
-      this.ctx.fillText('!', vehicle.x + vehicle.width/2 - 5, vehicle.y - 5);
-    }
+    // Rust runs fixed-size steps internally, we only hand it elapsed time
+    const dt = this.lastFrameTime === null ? 0 : time - this.lastFrameTime;
+    this.lastFrameTime = time;
+    this.pollGamepad();
+    const alpha = this.gameState.update(dt);
+    this.handleEvents(this.gameState.drain_events());
     
-    // Add chase indicators
-    if (vehicle.is_being_chased) {
-      this.ctx.fillStyle = 'red';
-      this.ctx.font = '12px Arial';
-      this.ctx.fillText('🏃', vehicle.x + vehicle.width/2 - 8, vehicle.y - 8);
-    }
+    // Render using JavaScript
+    this.render(alpha);
     
-    if (vehicle.is_chasing) {
-      this.ctx.fillStyle = 'blue';
-      this.ctx.font = '12px Arial';
-      this.ctx.fillText('👮', vehicle.x + vehicle.width/2 - 8, vehicle.y - 8);
-    }
+    requestAnimationFrame((nextTime) => this.gameLoop(nextTime));
   }
 
-  renderRegularVehicle(vehicle) {
-    const direction = vehicle.moving_right ? 'right' : 'left';
-    let imageKey = `${vehicle.vehicle_type}_${direction}`;
-    
-    // Use regular police image for PoliceChase
-    if (vehicle.vehicle_type === 'PoliceChase') {
-      imageKey = `Police_${direction}`;
-    }
-    
-    const image = this.vehicleImages[imageKey];
-    
-    if (image && image.complete) {
-      // Add special effects
-      if (vehicle.vehicle_type === 'PoliceChase') {
-        // Flashing blue-red lights for police chase
-        const time = Date.now() * 0.01;
-        const flash = Math.sin(time) > 0;
-        this.ctx.shadowColor = flash ? '#ff0000' : '#0000ff';
-        this.ctx.shadowBlur = 12;
-      } else if (vehicle.avoiding) {
-        // Yellow glow for avoiding vehicles
-        this.ctx.shadowColor = '#ffff00';
-        this.ctx.shadowBlur = 8;
-      }
-      
-      this.ctx.drawImage(image, vehicle.x, vehicle.y, vehicle.width, vehicle.height);
-      
-      // Clear shadow effects
-      this.ctx.shadowBlur = 0;
-    } else {
-      // Fallback to colored rectangle if image not loaded
-      let color = '#ffcc00';
-      switch (vehicle.vehicle_type) {
-        case 'Police': color = '#0066ff'; break;
-        case 'PoliceChase': color = '#0044aa'; break; // Darker blue for chase car
-        case 'Civil': color = '#888888'; break;
-        case 'Delivery': color = '#ff6600'; break;
-        case 'Taxi': color = '#ffcc00'; break;
-        case 'TrafficOrb': color = '#00ccff'; break; // Cyan for orbs (handled by renderOrb)
+  handleEvents(events) {
+    for (const event of events) {
+      switch (event.type) {
+        case 'CollidedWithObstacle':
+        case 'HitByVehicle':
+        case 'HitBillboard':
+          this.bumpSound.play(this.soundPaused);
+          this.flashFrames = 6;
+          break;
+        case 'PickupCollected':
+        case 'FarePickedUp':
+        case 'FareDelivered':
+          this.bumpSound.play(this.soundPaused);
+          break;
+        case 'GameOver':
+          this.boomSound.play(this.soundPaused);
+          break;
       }
-      
-      this.ctx.fillStyle = color;
-      this.ctx.fillRect(vehicle.x, vehicle.y, vehicle.width, vehicle.height);
     }
   }
 
-  renderTrain(vehicle) {
-    const trainLength = vehicle.width;
-    const carWidth = 40; // Each train car is about 40px
-    const numCars = Math.ceil(trainLength / carWidth);
+  // Rust decides what to draw, where and in which order (see
+  // rust-game/src/render.rs); this just replays its command buffer
+  render(alpha = 1) {
+    this.ctx.clearRect(0, 0, this.canvas.width, this.canvas.height);
     
-    for (let i = 0; i < numCars; i++) {
-      const carX = vehicle.x + (i * carWidth);
-      const actualCarWidth = Math.min(carWidth, trainLength - (i * carWidth));
+    this.gameState.fill_render_buffer(alpha);
+    const buffer = new Float32Array(
+      this.memory.buffer,
+      this.gameState.render_buffer_ptr(),
+      this.gameState.render_buffer_len()
+    );
+    const text = new Uint8Array(
+      this.memory.buffer,
+      this.gameState.render_text_ptr(),
+      this.gameState.render_text_len()
+    );
+    
+    for (let i = 0; i < buffer.length; i += RENDER_STRIDE) {
+      const index = buffer[i + 1];
+      const x = buffer[i + 2];
+      const y = buffer[i + 3];
+      const width = buffer[i + 4];
+      const height = buffer[i + 5];
+      const flags = buffer[i + 6];
+      const color = toCssColor(buffer[i + 8]);
       
-      let imageKey;
-      if (i === 0) {
-        imageKey = 'Train_front';
-      } else if (i === numCars - 1) {
-        imageKey = 'Train_back';
-      } else {
-        imageKey = 'Train_center';
+      this.ctx.save();
+      this.ctx.globalAlpha = buffer[i + 7];
+      if (flags & FLAG_GLOW) {
+        this.ctx.shadowColor = color;
+        this.ctx.shadowBlur = 8;
       }
       
-      const image = this.vehicleImages[imageKey];
-      
-      if (image && image.complete) {
-        this.ctx.drawImage(image, carX, vehicle.y, actualCarWidth, vehicle.height);
+      if (flags & FLAG_FILL) {
+        this.ctx.fillStyle = color;
+        this.ctx.fillRect(x, y, width, height);
+      } else if (flags & FLAG_TEXT) {
+        const string = this.textDecoder.decode(text.subarray(index, index + width));
+        this.ctx.font = `${height}px Arial`;
+        this.ctx.textAlign = (flags & FLAG_CENTERED) ? 'center' : 'left';
+        if (flags & FLAG_OUTLINED) {
+          this.ctx.strokeStyle = 'black';
+          this.ctx.lineWidth = 3;
+          this.ctx.strokeText(string, x, y);
+        }
+        this.ctx.fillStyle = color;
+        this.ctx.fillText(string, x, y);
       } else {
-        // Fallback rendering
-        this.ctx.fillStyle = '#333333';
-        this.ctx.fillRect(carX, vehicle.y, actualCarWidth, vehicle.height);
-        
-        // Add car dividers
-        if (i > 0) {
-          this.ctx.strokeStyle = '#666666';
-          this.ctx.lineWidth = 1;
-          this.ctx.beginPath();
-          this.ctx.moveTo(carX, vehicle.y);
-          this.ctx.lineTo(carX, vehicle.y + vehicle.height);
-          this.ctx.stroke();
+        if (flags & FLAG_FLIP_X) {
+          this.ctx.translate(x + width, y);
+          this.ctx.scale(-1, 1);
+          this.ctx.drawImage(this.sprites[index], 0, 0, width, height);
+        } else {
+          this.ctx.drawImage(this.sprites[index], x, y, width, height);
         }
       }
-    }
-  }
-
-  renderOrb(vehicle) {
-    if (this.orbImage && this.orbImage.complete) {
-      // Add glow effect for orbs
-      this.ctx.save();
-      this.ctx.shadowColor = '#00ffff';
-      this.ctx.shadowBlur = 8;
-      
-      this.ctx.drawImage(this.orbImage, vehicle.x, vehicle.y, vehicle.width, vehicle.height);
-      this.ctx.restore();
-    } else {
-      // Fallback rendering - glowing circle
-      this.ctx.save();
-      this.ctx.shadowColor = '#00ffff';
-      this.ctx.shadowBlur = 10;
-      this.ctx.fillStyle = '#00ccff';
-      this.ctx.beginPath();
-      this.ctx.arc(vehicle.x + vehicle.width/2, vehicle.y + vehicle.height/2, vehicle.width/2, 0, 2 * Math.PI);
-      this.ctx.fill();
       
-      // Add border
-      this.ctx.strokeStyle = '#ffffff';
-      this.ctx.lineWidth = 1;
-      this.ctx.beginPath();
-      this.ctx.arc(vehicle.x + vehicle.width/2, vehicle.y + vehicle.height/2, vehicle.width/2, 0, 2 * Math.PI);
-      this.ctx.stroke();
       this.ctx.restore();
     }
-  }
-
-  renderBillboard(billboard) {
-    const imageKey = billboard.billboard_type;
-    const image = this.billboardImages[imageKey];
     
-    if (image && image.complete) {
-      this.ctx.drawImage(image, billboard.x, billboard.y, billboard.width, billboard.height);
-    } else {
-      // Fallback rendering
-      this.ctx.fillStyle = '#330066';
-      this.ctx.fillRect(billboard.x, billboard.y, billboard.width, billboard.height);
-      
-      // Add border for visibility
-      this.ctx.strokeStyle = '#ff00ff';
-      this.ctx.lineWidth = 2;
-      this.ctx.strokeRect(billboard.x, billboard.y, billboard.width, billboard.height);
-      
-      // Add text
-      this.ctx.fillStyle = '#ffffff';
-      this.ctx.font = '12px Arial';
-      this.ctx.fillText('AD', billboard.x + billboard.width/2 - 10, billboard.y + billboard.height/2 + 5);
+    // Brief red flash after taking a hit
+    if (this.flashFrames > 0) {
+      this.ctx.fillStyle = `rgba(255, 0, 0, ${0.05 * this.flashFrames})`;
+      this.ctx.fillRect(0, 0, this.canvas.width, this.canvas.height);
+      this.flashFrames--;
     }
     
+    this.speakerIcon.draw(this.ctx, this.soundPaused);
+    this.themeSound.play(this.soundPaused);
   }
 
-  renderBackgroundBuilding(building, offset) {
-    const x = building.x + offset;
-    
-    // Use building images based on building type - consistently map each type
-    let imageKey;
-    switch (building.building_type) {
-      case 'Tall':
-        imageKey = 'TallTower';
-        break;
-      case 'Wide':
-        imageKey = 'WideTower';  
-        break;
-      case 'Medium':
-        imageKey = 'TallTower';
-        break;
-      default:
-        // Fallback for any unknown types
-        imageKey = 'TallTower';
-    }
-    
-    const image = this.buildingImages[imageKey];
-    if (image && image.complete) {
-      this.ctx.drawImage(image, x, building.y, building.width, building.height);
-    } else {
-      // Fallback to a darker, more subtle gray for background buildings
-      this.ctx.fillStyle = '#334455';
-      this.ctx.fillRect(x, building.y, building.width, building.height);
+  resumeSnapshot() {
+    const saved = localStorage.getItem(SNAPSHOT_KEY);
+    if (!saved) return;
+    localStorage.removeItem(SNAPSHOT_KEY);
+    try {
+      const bytes = Uint8Array.from(atob(saved), (c) => c.charCodeAt(0));
+      this.gameState.load_snapshot(bytes);
+    } catch (error) {
+      console.warn('Discarding unreadable saved session:', error);
     }
   }
 
-  renderObstacle(obstacle) {
-    const image = this.obstacleImages[obstacle.obstacle_type];
-
-    if (image && image.complete) {
-        this.ctx.drawImage(image, obstacle.x, obstacle.y, obstacle.width, obstacle.height);
-    } else {
-        // Fallback rendering
-        this.ctx.fillStyle = '#808080'; // Gray color for unloaded obstacles
-        this.ctx.fillRect(obstacle.x, obstacle.y, obstacle.width, obstacle.height);
+  suspendSnapshot() {
+    if (!this.gameState) return;
+    if (this.gameState.is_game_over()) {
+      localStorage.removeItem(SNAPSHOT_KEY);
+      return;
     }
+    const bytes = this.gameState.save_snapshot();
+    let binary = '';
+    bytes.forEach((b) => { binary += String.fromCharCode(b); });
+    localStorage.setItem(SNAPSHOT_KEY, btoa(binary));
   }
 
   cleanup() {
     this.running = false;
+    document.removeEventListener('visibilitychange', this.onVisibilityChange);
+    window.removeEventListener('pagehide', this.onPageHide);
     if (this.gameState) {
+      this.suspendSnapshot();
       this.gameState.free();
     }
   }
@@ -1,5 +1,20 @@
 use serde::{Deserialize, Serialize};
-use js_sys::Math;
+use crate::broadphase::{EntityRef, SpatialGrid};
+use crate::difficulty::Difficulty;
+use crate::districts::{DistrictProfile, DistrictState};
+use crate::physics::Aabb;
+use crate::rng::Rng;
+use crate::vehicle_archetypes::{Direction, Lane, TrafficRole, VehicleArchetype, VehicleRegistry};
+
+// Parallax scroll speeds in pixels per second at the starting pace;
+// billboards ride with the foreground
+const FAR_BUILDING_SPEED: f32 = 18.0;
+const DISTANT_BUILDING_SPEED: f32 = 15.0;
+// Broadphase reach for traffic avoidance; covers the longest look-ahead
+// (1.5 x 70 px) and how far apart two vehicles' tops can be in one lane
+const AVOID_QUERY_RANGE: f32 = 110.0;
+const AVOID_LANE_RANGE: f32 = 35.0;
+const OVERTAKE_BOOST: f32 = 1.3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackgroundVehicle {
@@ -12,13 +27,13 @@ pub struct BackgroundVehicle {
     pub moving_right: bool,
     pub base_y: f32,        // Original lane position
     pub avoiding: bool,     // Currently avoiding another vehicle
-    pub avoid_timer: u32,   // How long to maintain avoidance maneuver
+    pub avoid_timer: f32,   // Seconds left in the current avoidance maneuver
     pub damage: f32,        // Damage this vehicle deals
     pub is_being_chased: bool, // If this vehicle is being chased by police
     pub is_chasing: bool,   // If this is a police car chasing someone
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum VehicleType {
     Taxi,
     Police,
@@ -30,93 +45,49 @@ pub enum VehicleType {
 }
 
 impl BackgroundVehicle {
-    pub fn new_right_moving(x: f32) -> Self {
-        // Weighted random selection - trains 5%, orbs 8%, rest regular
-        let rand_val = Math::random();
-        let vehicle_type = if rand_val < 0.05 {
-            VehicleType::Train // 5% chance for trains
-        } else if rand_val < 0.13 {
-            VehicleType::TrafficOrb // 8% chance for orbs
-        } else {
-            let regular_types = [VehicleType::Taxi, VehicleType::Civil, VehicleType::Delivery];
-            let type_idx = ((rand_val - 0.13) / 0.87 * 3.0) as usize;
-            regular_types[type_idx.min(2)].clone()
-        };
-        
-        let base_y = 50.0; // Top traffic lane
-        
-        // Different properties based on vehicle type - reduced sizes to match visual
-        let (width, height, damage, speed_base, speed_variance) = match vehicle_type {
-            VehicleType::Train => (120.0, 25.0, 8.0, 1.5, 0.5), // Long, slow, high damage
-            VehicleType::PoliceChase => (35.0, 18.0, 6.0, 6.0, 1.0), // Fast police car - smaller collision box
-            VehicleType::TrafficOrb => (16.0, 16.0, 3.0, 2.0, 1.5), // Smaller floating orbs
-            VehicleType::Delivery => (40.0, 20.0, 4.0, 2.5, 1.0), // Smaller delivery truck
-            VehicleType::Taxi => (35.0, 18.0, 2.0, 3.0, 2.0), // Smaller taxi
-            VehicleType::Civil => (34.0, 18.0, 1.5, 3.0, 2.0), // Smaller civil car
-            VehicleType::Police => unreachable!(), // Only PoliceChase now
-        };
-        
+    fn new(archetype: &VehicleArchetype, lane: &Lane, x: f32, speed: f32) -> Self {
         BackgroundVehicle {
             x,
-            y: base_y,
-            width,
-            height,
-            speed: speed_base + (Math::random() * speed_variance) as f32,
-            vehicle_type,
-            moving_right: true,
-            base_y,
+            y: lane.y,
+            width: archetype.width,
+            height: archetype.height,
+            speed,
+            vehicle_type: archetype.vehicle_type.clone(),
+            moving_right: lane.direction == Direction::Right,
+            base_y: lane.y,
             avoiding: false,
-            avoid_timer: 0,
-            damage,
-            is_being_chased: false,
-            is_chasing: false,
+            avoid_timer: 0.0,
+            damage: archetype.damage,
+            is_being_chased: archetype.role == TrafficRole::Fleeing,
+            is_chasing: archetype.role == TrafficRole::Chasing,
         }
     }
 
-    pub fn new_left_moving(x: f32) -> Self {
-        // Spawn regular vehicles and orbs going left - no trains or police
-        let rand_val = Math::random();
-        let vehicle_type = if rand_val < 0.15 {
-            VehicleType::TrafficOrb // 15% chance for orbs in left lane
-        } else {
-            let regular_types = [VehicleType::Taxi, VehicleType::Civil, VehicleType::Delivery];
-            let type_idx = ((rand_val - 0.15) / 0.85 * 3.0) as usize;
-            regular_types[type_idx.min(2)].clone()
-        };
-        
-        let base_y = 90.0; // Bottom traffic lane
-        
-        // Different properties based on vehicle type - no trains for left-moving, reduced sizes
-        let (width, height, damage, speed_base, speed_variance) = match vehicle_type {
-            VehicleType::TrafficOrb => (16.0, 16.0, 3.0, 1.8, 1.2), // Smaller orbs going left
-            VehicleType::Delivery => (40.0, 20.0, 4.0, 2.0, 1.0), // Smaller delivery truck
-            VehicleType::Taxi => (35.0, 18.0, 2.0, 2.5, 1.5), // Smaller taxi
-            VehicleType::Civil => (34.0, 18.0, 1.5, 2.5, 1.5), // Smaller civil car
-            VehicleType::Train => unreachable!(), // Trains only go right
-            VehicleType::PoliceChase => unreachable!(), // Police chase only go right
-            VehicleType::Police => unreachable!(), // Only PoliceChase now
-        };
-        
-        BackgroundVehicle {
-            x,
-            y: base_y,
-            width,
-            height,
-            speed: speed_base + (Math::random() * speed_variance) as f32,
-            vehicle_type,
-            moving_right: false,
-            base_y,
-            avoiding: false,
-            avoid_timer: 0,
-            damage,
-            is_being_chased: false,
-            is_chasing: false,
-        }
+    fn spawn(role: TrafficRole, x: f32, registry: &VehicleRegistry, rng: &mut Rng) -> Self {
+        let archetype = registry.random(role, rng);
+        BackgroundVehicle::new(archetype, registry.lane_of(archetype), x, archetype.roll_speed(rng))
     }
 
-    pub fn update(&mut self, other_vehicles: &[BackgroundVehicle]) {
-        let my_id = (self.x * 1000.0) as i32 + (self.y * 1000.0) as i32; // Create unique ID
-        
+    pub fn new_right_moving(x: f32, registry: &VehicleRegistry, rng: &mut Rng) -> Self {
+        BackgroundVehicle::spawn(TrafficRole::Right, x, registry, rng)
+    }
+
+    pub fn new_left_moving(x: f32, registry: &VehicleRegistry, rng: &mut Rng) -> Self {
+        BackgroundVehicle::spawn(TrafficRole::Left, x, registry, rng)
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+
+    // Everything the avoidance check below could react to lies in here:
+    // up to 1.5x the avoid distance ahead (to a train's front or back) and
+    // within a lane's height
+    fn avoidance_area(&self) -> Aabb {
+        self.aabb().expanded(AVOID_QUERY_RANGE, AVOID_LANE_RANGE)
+    }
+
+    pub fn update(&mut self, nearby: &[&BackgroundVehicle], dt: f32) {
         // Trains are less likely to avoid others (they're big and have right of way)
         let mut should_avoid = false;
         let avoid_distance = if matches!(self.vehicle_type, VehicleType::Train) {
@@ -125,12 +96,7 @@ impl BackgroundVehicle {
             70.0 // Regular vehicles avoid more
         };
         
-        for other in other_vehicles {
-            let other_id = (other.x * 1000.0) as i32 + (other.y * 1000.0) as i32;
-            if my_id == other_id {
-                continue; // Skip self using unique ID
-            }
-            
+        for other in nearby {
             // Avoid vehicles in same direction that are slower OR trains (always avoid trains)
             let should_check = if matches!(other.vehicle_type, VehicleType::Train) {
                 self.moving_right == other.moving_right // Always avoid trains in same direction
@@ -153,7 +119,7 @@ impl BackgroundVehicle {
                 };
                 
                 // Check if vehicles are in same lane and one is ahead
-                let y_overlap = (self.y - other.y).abs() < 35.0; // Wider detection for trains
+                let y_overlap = (self.y - other.y).abs() < AVOID_LANE_RANGE; // Wider detection for trains
                 
                 // For trains, use larger avoidance distance and check both front and back
                 let effective_distance = if matches!(other.vehicle_type, VehicleType::Train) {
@@ -180,20 +146,21 @@ impl BackgroundVehicle {
         if should_avoid && !self.avoiding {
             self.avoiding = true;
             // Much longer avoidance time to fully clear obstacles
-            self.avoid_timer = 120; // Longer avoidance time to clear long trains
+            self.avoid_timer = 2.0; // Longer avoidance time to clear long trains
         } else if should_avoid && self.avoiding {
             // If still avoiding and still need to avoid, reset timer to continue avoiding
-            self.avoid_timer = self.avoid_timer.max(60); // Don't let it drop too low while still avoiding
+            self.avoid_timer = self.avoid_timer.max(1.0); // Don't let it drop too low while still avoiding
         }
         
         if self.avoiding {
-            self.avoid_timer -= 1;
+            self.avoid_timer = (self.avoid_timer - dt).max(0.0);
             
             // Tight avoidance movement - just enough to get around
-            let avoid_speed = 1.2; // Gentler movement
-            let avoid_direction = if self.base_y < 70.0 { // Top lane
+            let avoid_speed = 72.0 * dt; // Gentler movement
+            // Rightbound traffic dodges up and leftbound down, away from each other
+            let avoid_direction = if self.moving_right {
                 -avoid_speed // Move up slightly
-            } else { // Bottom lane
+            } else {
                 avoid_speed // Move down slightly
             };
             
@@ -203,13 +170,13 @@ impl BackgroundVehicle {
             self.y = self.y.clamp(self.base_y - 20.0, self.base_y + 20.0);
             
             // Only stop avoiding if timer is done AND we're not still detecting obstacles
-            if self.avoid_timer <= 0 && !should_avoid {
+            if self.avoid_timer <= 0.0 && !should_avoid {
                 self.avoiding = false;
             }
         } else {
             // Very slowly return to base lane when not avoiding to prevent premature returns
             if (self.y - self.base_y).abs() > 1.0 {
-                let return_speed = 0.3; // Much slower return to base lane
+                let return_speed = 18.0 * dt; // Much slower return to base lane
                 if self.y > self.base_y {
                     self.y -= return_speed;
                 } else {
@@ -219,26 +186,14 @@ impl BackgroundVehicle {
         }
         
         // Move horizontally - speed up when avoiding (overtaking)
-        let current_speed = if self.avoiding {
-            self.speed * 1.3 // 30% speed boost when overtaking
-        } else {
-            self.speed
-        };
-        
-        if self.moving_right {
-            self.x += current_speed;
-        } else {
-            self.x -= current_speed;
-        }
+        self.x += self.velocity().0 * dt;
     }
     
-    pub fn check_collision_with_player(&self, player_x: f32, player_y: f32, player_width: f32, player_height: f32) -> bool {
-        let below_the_top = player_y + player_height > self.y;
-        let above_the_bottom = player_y < self.y + self.height;
-        let to_the_left_of_right = player_x < self.x + self.width;
-        let to_the_right_of_left = player_width + player_x - 2.0 > self.x;
-
-        below_the_top && above_the_bottom && to_the_left_of_right && to_the_right_of_left
+    // Horizontal travel speed, with a 30% boost when avoiding (overtaking).
+    // Lane drift is slow enough that swept collisions leave it out.
+    pub fn velocity(&self) -> (f32, f32) {
+        let speed = if self.avoiding { self.speed * OVERTAKE_BOOST } else { self.speed };
+        (if self.moving_right { speed } else { -speed }, 0.0)
     }
 
     pub fn is_off_screen(&self, canvas_width: f32) -> bool {
@@ -267,10 +222,11 @@ pub enum BuildingType {
 }
 
 impl Building {
-    pub fn new(x: f32) -> Self {
+    pub fn new(x: f32, profile: &DistrictProfile, rng: &mut Rng) -> Self {
         let building_types = [BuildingType::Tall, BuildingType::Wide, BuildingType::Medium];
-        let type_idx = (Math::random() * 3.0) as usize;
-        
+        // Weighted by the district's building mix
+        let type_idx = rng.weighted_index(&profile.building_weights);
+
         let (width, height, y) = match &building_types[type_idx] {
             BuildingType::Tall => (40.0, 180.0, 150.0),
             BuildingType::Wide => (80.0, 120.0, 210.0),
@@ -306,27 +262,26 @@ pub enum BillboardType {
 }
 
 impl Billboard {
-    pub fn new(x: f32) -> Self {
-        let billboard_types = [
-            BillboardType::FirstBreak,
-            BillboardType::SecondBreak,
-            BillboardType::Security,
-            BillboardType::Security2,
-            BillboardType::SharkMovie,
-        ];
-        let type_idx = (Math::random() * 5.0) as usize;
+    pub fn new(x: f32, profile: &DistrictProfile, rng: &mut Rng) -> Self {
+        let billboard_types = profile.billboards;
+        let type_idx = (rng.next_f64() * billboard_types.len() as f64) as usize;
         
         Billboard {
             x,
             y: 10.0, // Position to hang down into flight area
             width: 84.0,  // 60% of JavaScript game size (140 * 0.6)
             height: 48.0, // 60% of JavaScript game size (80 * 0.6)
-            billboard_type: billboard_types[type_idx.min(4)].clone(),
+            billboard_type: billboard_types[type_idx.min(billboard_types.len() - 1)].clone(),
         }
     }
 
-    pub fn update(&mut self, speed: f32) {
-        self.x -= speed;
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+
+    // `scroll` is how far the foreground moved this step, in px
+    pub fn update(&mut self, scroll: f32) {
+        self.x -= scroll;
     }
 
     pub fn is_off_screen(&self) -> bool {
@@ -342,65 +297,24 @@ pub struct BackgroundManager {
     pub billboards: Vec<Billboard>,
     pub far_building_offset: f32,
     pub distant_building_offset: f32,
-    pub spawn_timer: u32,
+    pub spawn_timer: u32, // Fixed simulation steps since start, drives spawn cadence
+    pub district: DistrictState,
     canvas_width: f32,
 }
 
 impl BackgroundVehicle {
-    pub fn new_chase_pair(x: f32) -> (BackgroundVehicle, BackgroundVehicle) {
-        let base_y = 50.0;
+    pub fn new_chase_pair(x: f32, registry: &VehicleRegistry, rng: &mut Rng) -> (BackgroundVehicle, BackgroundVehicle) {
         let spacing = -80.0; // Police behind target
-        
-        // Create target vehicle (being chased)
-        let target_types = [VehicleType::Civil, VehicleType::Taxi, VehicleType::Delivery];
-        let target_type = target_types[(Math::random() * 3.0) as usize].clone();
-        
-        let (width, height, damage, speed_base, speed_variance) = match target_type {
-            VehicleType::Delivery => (45.0, 22.0, 4.0, 4.5, 0.5), // Faster when being chased
-            VehicleType::Taxi => (40.0, 20.0, 2.0, 4.2, 0.5),
-            VehicleType::Civil => (38.0, 20.0, 1.5, 4.0, 0.5),
-            _ => unreachable!(),
-        };
-        
-        let target = BackgroundVehicle {
-            x,
-            y: base_y,
-            width,
-            height,
-            speed: speed_base + (Math::random() * speed_variance) as f32,
-            vehicle_type: target_type,
-            moving_right: true,
-            base_y,
-            avoiding: false,
-            avoid_timer: 0,
-            damage,
-            is_being_chased: true,
-            is_chasing: false,
-        };
-        
-        // Create police car (chasing)
-        let police = BackgroundVehicle {
-            x: x + spacing,
-            y: base_y,
-            width: 40.0,
-            height: 20.0,
-            speed: target.speed + 0.8, // Police slightly faster to catch up
-            vehicle_type: VehicleType::PoliceChase,
-            moving_right: true,
-            base_y,
-            avoiding: false,
-            avoid_timer: 0,
-            damage: 6.0,
-            is_being_chased: false,
-            is_chasing: true,
-        };
-        
+
+        let target = BackgroundVehicle::spawn(TrafficRole::Fleeing, x, registry, rng);
+        // The registry makes sure police are faster, so they catch up
+        let police = BackgroundVehicle::spawn(TrafficRole::Chasing, x + spacing, registry, rng);
         (target, police)
     }
 }
 
 impl BackgroundManager {
-    pub fn new(canvas_width: f32) -> Self {
+    pub fn new(canvas_width: f32, rng: &mut Rng) -> Self {
         let mut manager = BackgroundManager {
             far_buildings: Vec::new(),
             distant_buildings: Vec::new(),
@@ -409,37 +323,43 @@ impl BackgroundManager {
             far_building_offset: 0.0,
             distant_building_offset: 0.0,
             spawn_timer: 0,
+            district: DistrictState::new(),
             canvas_width,
         };
 
         // Initialize some background buildings
-        manager.generate_initial_buildings();
-        manager.generate_initial_distant_buildings();
+        manager.generate_initial_buildings(rng);
+        manager.generate_initial_distant_buildings(rng);
         manager.generate_initial_traffic();
-        manager.generate_initial_billboards();
+        manager.generate_initial_billboards(rng);
         
         manager
     }
 
-    pub fn update(&mut self) {
+    // `grid` must have been rebuilt from the current traffic list
+    pub fn update(&mut self, difficulty: &Difficulty, vehicles: &VehicleRegistry, grid: &SpatialGrid, rng: &mut Rng, dt: f32) {
+        // Districts change with distance travelled in the foreground
+        let scroll = difficulty.scroll_speed * dt;
+        self.district.advance(scroll, rng);
+
         // Update parallax offset for far buildings - slower than foreground
         // Negative offset moves buildings left (off screen), creating proper parallax
-        self.far_building_offset -= 0.3; // Move buildings left slowly
-        self.distant_building_offset -= 0.25; // Move distant buildings slightly slower
+        self.far_building_offset -= FAR_BUILDING_SPEED * difficulty.pace() * dt; // Move buildings left slowly
+        self.distant_building_offset -= DISTANT_BUILDING_SPEED * difficulty.pace() * dt; // Move distant buildings slightly slower
         
         // When buildings have scrolled far enough left, reset offset and regenerate buildings
         if self.far_building_offset < -300.0 {
             self.far_building_offset = 0.0;
             // Clear old buildings and generate fresh ones
             self.far_buildings.clear();
-            self.generate_initial_buildings();
+            self.generate_initial_buildings(rng);
         }
 
         if self.distant_building_offset < -300.0 {
             self.distant_building_offset = 0.0;
             // Clear old distant buildings and generate fresh ones
             self.distant_buildings.clear();
-            self.generate_initial_distant_buildings();
+            self.generate_initial_distant_buildings(rng);
         }
 
         // Don't update individual building positions - use offset for consistent parallax
@@ -447,7 +367,7 @@ impl BackgroundManager {
 
         // Update billboards
         for billboard in &mut self.billboards {
-            billboard.update(2.0); // Move slightly faster than buildings
+            billboard.update(scroll); // Move slightly faster than buildings
         }
 
         // Remove off-screen buildings (accounting for parallax offset) and add new ones
@@ -455,46 +375,55 @@ impl BackgroundManager {
             let effective_x = building.x + self.far_building_offset;
             effective_x > -building.width - 50.0
         });
-        self.spawn_buildings_if_needed();
+        self.spawn_buildings_if_needed(rng);
 
         // Remove off-screen distant buildings and add new ones
         self.distant_buildings.retain(|building| {
             let effective_x = building.x + self.distant_building_offset;
             effective_x > -building.width - 50.0
         });
-        self.spawn_distant_buildings_if_needed();
+        self.spawn_distant_buildings_if_needed(rng);
 
         // Remove off-screen billboards and add new ones
         self.billboards.retain(|billboard| !billboard.is_off_screen());
-        self.spawn_billboards_if_needed();
-
-        // Update traffic vehicles with collision avoidance
-        // Process vehicles in multiple passes for better avoidance
-        let vehicles_count = self.traffic_vehicles.len();
-        for i in 0..vehicles_count {
-            let vehicles_snapshot: Vec<BackgroundVehicle> = self.traffic_vehicles.clone();
-            self.traffic_vehicles[i].update(&vehicles_snapshot);
+        self.spawn_billboards_if_needed(rng);
+
+        // Update traffic vehicles with collision avoidance. Everyone reacts
+        // to where the others were at the start of the step.
+        let vehicles_snapshot = self.traffic_vehicles.clone();
+        for (i, vehicle) in self.traffic_vehicles.iter_mut().enumerate() {
+            let nearby: Vec<&BackgroundVehicle> = grid
+                .query(&vehicle.avoidance_area())
+                .into_iter()
+                .filter_map(|entity| match entity {
+                    EntityRef::Vehicle(j) if j != i => vehicles_snapshot.get(j),
+                    _ => None,
+                })
+                .collect();
+            vehicle.update(&nearby, dt);
         }
 
         // Remove off-screen vehicles and spawn new ones
         self.traffic_vehicles.retain(|vehicle| !vehicle.is_off_screen(self.canvas_width));
-        self.spawn_traffic_if_needed();
+        self.spawn_traffic_if_needed(difficulty, vehicles, rng);
     }
 
-    fn generate_initial_buildings(&mut self) {
+    fn generate_initial_buildings(&mut self, rng: &mut Rng) {
         let mut spawn_x = 0.0;
         for _ in 0..15 {
-            let building = Building::new(spawn_x);
-            spawn_x += building.width + (Math::random() * 50.0) as f32 + 20.0;
+            let profile = self.district.spawn_profile(rng);
+            let building = Building::new(spawn_x, profile, rng);
+            spawn_x += building.width + ((rng.next_f64() * 50.0) as f32 + 20.0) * profile.building_gap;
             self.far_buildings.push(building);
         }
     }
 
-    fn generate_initial_distant_buildings(&mut self) {
+    fn generate_initial_distant_buildings(&mut self, rng: &mut Rng) {
         let mut spawn_x = 0.0;
         for _ in 0..12 {
-            let building = Building::new(spawn_x);
-            spawn_x += building.width + (Math::random() * 80.0) as f32 + 40.0; // Wider spacing for distant buildings
+            let profile = self.district.spawn_profile(rng);
+            let building = Building::new(spawn_x, profile, rng);
+            spawn_x += building.width + ((rng.next_f64() * 80.0) as f32 + 40.0) * profile.building_gap; // Wider spacing for distant buildings
             self.distant_buildings.push(building);
         }
     }
@@ -503,74 +432,83 @@ impl BackgroundManager {
         // Start with no traffic - vehicles will spawn during gameplay
     }
 
-    fn generate_initial_billboards(&mut self) {
+    fn generate_initial_billboards(&mut self, rng: &mut Rng) {
         // Start with a few billboards with guaranteed spacing
         for i in 0..3 {
             let spacing = 400.0; // Wider spacing to prevent overlaps
-            let x = (i as f32) * spacing + (Math::random() * 100.0) as f32;
-            self.billboards.push(Billboard::new(x));
+            let x = (i as f32) * spacing + (rng.next_f64() * 100.0) as f32;
+            let profile = self.district.spawn_profile(rng);
+            self.billboards.push(Billboard::new(x, profile, rng));
         }
     }
 
-    fn spawn_buildings_if_needed(&mut self) {
+    fn spawn_buildings_if_needed(&mut self, rng: &mut Rng) {
         if let Some(last_building) = self.far_buildings.last() {
             let effective_x = last_building.x + last_building.width + self.far_building_offset;
             if effective_x < self.canvas_width + 200.0 {
-                let spawn_x = last_building.x + last_building.width + (Math::random() * 50.0) as f32 + 20.0;
-                self.far_buildings.push(Building::new(spawn_x));
+                let profile = self.district.spawn_profile(rng);
+                let spawn_x = last_building.x + last_building.width + ((rng.next_f64() * 50.0) as f32 + 20.0) * profile.building_gap;
+                self.far_buildings.push(Building::new(spawn_x, profile, rng));
             }
         }
     }
 
-    fn spawn_distant_buildings_if_needed(&mut self) {
+    fn spawn_distant_buildings_if_needed(&mut self, rng: &mut Rng) {
         if let Some(last_building) = self.distant_buildings.last() {
             let effective_x = last_building.x + last_building.width + self.distant_building_offset;
             if effective_x < self.canvas_width + 300.0 {
-                let spawn_x = last_building.x + last_building.width + (Math::random() * 80.0) as f32 + 40.0;
-                self.distant_buildings.push(Building::new(spawn_x));
+                let profile = self.district.spawn_profile(rng);
+                let spawn_x = last_building.x + last_building.width + ((rng.next_f64() * 80.0) as f32 + 40.0) * profile.building_gap;
+                self.distant_buildings.push(Building::new(spawn_x, profile, rng));
             }
         }
     }
 
-    fn spawn_traffic_if_needed(&mut self) {
+    fn spawn_traffic_if_needed(&mut self, difficulty: &Difficulty, vehicles: &VehicleRegistry, rng: &mut Rng) {
         self.spawn_timer += 1;
+        let density = self.district.traffic_density() * difficulty.traffic_chance;
+        let spawned_from = self.traffic_vehicles.len();
         
         // More frequent spawning with varied timing
         // Spawn right-moving vehicles or chase pairs
-        if self.spawn_timer % 90 == 0 && Math::random() < 0.8 {
-            if Math::random() < 0.03 { // 3% chance for chase pair
-                let (target, police) = BackgroundVehicle::new_chase_pair(-50.0);
+        if self.spawn_timer.is_multiple_of(90) && rng.next_f64() < 0.8 * density as f64 {
+            if rng.next_f64() < 0.03 { // 3% chance for chase pair
+                let (target, police) = BackgroundVehicle::new_chase_pair(-50.0, vehicles, rng);
                 self.traffic_vehicles.push(target);
                 self.traffic_vehicles.push(police);
             } else {
-                self.traffic_vehicles.push(BackgroundVehicle::new_right_moving(-50.0));
+                self.traffic_vehicles.push(BackgroundVehicle::new_right_moving(-50.0, vehicles, rng));
             }
         }
 
         // Spawn left-moving vehicles with different timing to avoid synchronization
-        if self.spawn_timer % 80 == 40 && Math::random() < 0.75 {
-            self.traffic_vehicles.push(BackgroundVehicle::new_left_moving(self.canvas_width + 50.0));
+        if self.spawn_timer % 80 == 40 && rng.next_f64() < 0.75 * density as f64 {
+            self.traffic_vehicles.push(BackgroundVehicle::new_left_moving(self.canvas_width + 50.0, vehicles, rng));
         }
 
         // Occasionally spawn clusters of vehicles for variety
-        if self.spawn_timer % 300 == 0 && Math::random() < 0.3 {
+        if self.spawn_timer.is_multiple_of(300) && rng.next_f64() < 0.3 * density as f64 {
             // Spawn a small convoy
             for i in 0..3 {
-                let spacing = 80.0 + (Math::random() * 40.0) as f32;
-                if Math::random() < 0.5 {
-                    self.traffic_vehicles.push(BackgroundVehicle::new_right_moving(-50.0 - i as f32 * spacing));
+                let spacing = 80.0 + (rng.next_f64() * 40.0) as f32;
+                if rng.next_f64() < 0.5 {
+                    self.traffic_vehicles.push(BackgroundVehicle::new_right_moving(-50.0 - i as f32 * spacing, vehicles, rng));
                 } else {
-                    self.traffic_vehicles.push(BackgroundVehicle::new_left_moving(self.canvas_width + 50.0 + i as f32 * spacing));
+                    self.traffic_vehicles.push(BackgroundVehicle::new_left_moving(self.canvas_width + 50.0 + i as f32 * spacing, vehicles, rng));
                 }
             }
         }
+
+        for vehicle in &mut self.traffic_vehicles[spawned_from..] {
+            vehicle.speed *= difficulty.vehicle_speed;
+        }
     }
 
-    fn spawn_billboards_if_needed(&mut self) {
+    fn spawn_billboards_if_needed(&mut self, rng: &mut Rng) {
         // Spawn billboards with proper spacing check
-        if self.spawn_timer % 200 == 100 && Math::random() < 0.4 {
+        if self.spawn_timer % 200 == 100 && rng.next_f64() < 0.4 * self.district.billboard_density() as f64 {
             let min_spacing = 250.0; // Minimum distance between billboards
-            let spawn_x = self.canvas_width + (Math::random() * 200.0) as f32 + 100.0;
+            let spawn_x = self.canvas_width + (rng.next_f64() * 200.0) as f32 + 100.0;
             
             // Check if there's enough space from ALL existing billboards
             let can_spawn = self.billboards.iter().all(|existing| {
@@ -579,7 +517,8 @@ impl BackgroundManager {
             });
             
             if can_spawn {
-                self.billboards.push(Billboard::new(spawn_x));
+                let profile = self.district.spawn_profile(rng);
+                self.billboards.push(Billboard::new(spawn_x, profile, rng));
             }
         }
     }
@@ -0,0 +1,187 @@
+// Headless batch runner: plays many seeded games with a scripted or random
+// input policy and prints aggregate stats for tuning spawn rates.
+//
+//   cargo run --release --bin bad-sky-sim -- --games 200 --seed 1 --policy random
+
+use rust_game::background::VehicleType;
+use rust_game::entities::ObstacleType;
+use rust_game::game_state::{GameState, FIXED_DT};
+use rust_game::input::{Action, Axis, InputSource};
+use rust_game::physics::GameOverCause;
+use rust_game::rng::Rng;
+use std::collections::BTreeMap;
+use std::process;
+
+#[derive(Debug, Clone, Copy)]
+enum Policy {
+    Idle,   // Never touch the controls
+    Random, // Mash random direction combos
+    Cruise, // Hold a fixed cruising altitude and position with analog thrust
+}
+
+impl Policy {
+    fn parse(name: &str) -> Option<Policy> {
+        match name {
+            "idle" => Some(Policy::Idle),
+            "random" => Some(Policy::Random),
+            "cruise" => Some(Policy::Cruise),
+            _ => None,
+        }
+    }
+}
+
+struct Options {
+    games: u32,
+    seed: u64,
+    policy: Policy,
+    max_frames: u32,
+}
+
+struct Totals {
+    score: u64,
+    frames: u64,
+    causes: BTreeMap<GameOverCause, u32>,
+    survived: u32, // Games still running when max_frames was reached
+    obstacles: BTreeMap<ObstacleType, u32>,
+    vehicles: BTreeMap<VehicleType, u32>,
+    billboards: u32,
+    fares_delivered: u32,
+    fares_failed: u32,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: bad-sky-sim [--games N] [--seed START] [--policy idle|random|cruise] [--max-frames N]");
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        games: 100,
+        seed: 1,
+        policy: Policy::Random,
+        max_frames: 60 * 60 * 5, // Five minutes of play
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| usage());
+        match flag.as_str() {
+            "--games" => options.games = value.parse().unwrap_or_else(|_| usage()),
+            "--seed" => options.seed = value.parse().unwrap_or_else(|_| usage()),
+            "--policy" => options.policy = Policy::parse(&value).unwrap_or_else(|| usage()),
+            "--max-frames" => options.max_frames = value.parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    options
+}
+
+fn apply_policy(policy: Policy, state: &mut GameState, rng: &mut Rng) {
+    let source = InputSource::Keyboard;
+    match policy {
+        Policy::Idle => {}
+        Policy::Random => {
+            // Hold each combo for a quarter second or so, like a human would
+            if !state.frame.is_multiple_of(15) {
+                return;
+            }
+            state.set_action(source, Action::Right, rng.next_f64() < 0.3);
+            state.set_action(source, Action::Left, rng.next_f64() < 0.3);
+            state.set_action(source, Action::Up, rng.next_f64() < 0.4);
+            state.set_action(source, Action::Down, rng.next_f64() < 0.3);
+        }
+        Policy::Cruise => {
+            // Proportional steering towards a spot just behind the left third
+            let x_error = (160.0 - state.player.x) / 40.0;
+            let y_error = (110.0 - state.player.y) / 20.0;
+            state.set_axis(InputSource::Gamepad, Axis::Horizontal, x_error);
+            state.set_axis(InputSource::Gamepad, Axis::Vertical, y_error);
+        }
+    }
+}
+
+fn run_game(seed: u64, options: &Options, totals: &mut Totals) {
+    let mut state = GameState::new_with_seed(seed);
+    // Separate stream so the policy never perturbs the world's RNG
+    let mut policy_rng = Rng::new(seed ^ 0x5EED_CAB5);
+
+    while !state.game_over && state.frame < options.max_frames {
+        apply_policy(options.policy, &mut state, &mut policy_rng);
+        state.step();
+    }
+
+    totals.score += state.score as u64;
+    totals.frames += state.frame as u64;
+    match state.game_over_cause {
+        Some(cause) => *totals.causes.entry(cause).or_insert(0) += 1,
+        None => totals.survived += 1,
+    }
+    for (obstacle_type, count) in &state.collisions.obstacles {
+        *totals.obstacles.entry(obstacle_type.clone()).or_insert(0) += count;
+    }
+    for (vehicle_type, count) in &state.collisions.vehicles {
+        *totals.vehicles.entry(vehicle_type.clone()).or_insert(0) += count;
+    }
+    totals.billboards += state.collisions.billboards;
+    totals.fares_delivered += state.fares.delivered;
+    totals.fares_failed += state.fares.failed;
+}
+
+fn main() {
+    let options = parse_args();
+    if options.games == 0 {
+        usage();
+    }
+
+    let mut totals = Totals {
+        score: 0,
+        frames: 0,
+        causes: BTreeMap::new(),
+        survived: 0,
+        obstacles: BTreeMap::new(),
+        vehicles: BTreeMap::new(),
+        billboards: 0,
+        fares_delivered: 0,
+        fares_failed: 0,
+    };
+
+    for i in 0..options.games {
+        run_game(options.seed.wrapping_add(i as u64), &options, &mut totals);
+    }
+
+    let games = options.games as f64;
+    let avg_frames = totals.frames as f64 / games;
+    println!(
+        "games: {} (seeds {}..={}), policy: {:?}",
+        options.games,
+        options.seed,
+        options.seed.wrapping_add(options.games as u64 - 1),
+        options.policy
+    );
+    println!("average score: {:.1}", totals.score as f64 / games);
+    println!(
+        "average frames survived: {:.1} ({:.1}s)",
+        avg_frames,
+        avg_frames * FIXED_DT as f64
+    );
+
+    println!("game over causes:");
+    for (cause, count) in &totals.causes {
+        println!("  {:<20} {:>6} ({:.1}%)", format!("{:?}", cause), count, *count as f64 / games * 100.0);
+    }
+    if totals.survived > 0 {
+        println!("  {:<20} {:>6} ({:.1}%)", "hit frame cap", totals.survived, totals.survived as f64 / games * 100.0);
+    }
+
+    println!("obstacle hits by type:");
+    for (obstacle_type, count) in &totals.obstacles {
+        println!("  {:<20} {:>6}", format!("{:?}", obstacle_type), count);
+    }
+    println!("vehicle hits by type:");
+    for (vehicle_type, count) in &totals.vehicles {
+        println!("  {:<20} {:>6}", format!("{:?}", vehicle_type), count);
+    }
+    println!("billboard hits: {}", totals.billboards);
+    println!("fares delivered: {}, failed: {}", totals.fares_delivered, totals.fares_failed);
+}
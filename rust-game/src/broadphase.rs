@@ -0,0 +1,146 @@
+use crate::physics::Aabb;
+
+// Cells are a bit wider than the cab so most queries touch only a few
+const CELL_SIZE: f32 = 64.0;
+// Area covered by the grid: the canvas plus a margin for things spawning or
+// leaving. Anything further out lands in the edge cells, which stays correct
+// but slower.
+const MARGIN: f32 = 256.0;
+
+// What a grid entry refers to: an index into GameState::obstacles, the
+// background's traffic and billboard lists or the pickups, as of the last rebuild
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntityRef {
+    Obstacle(usize),
+    Vehicle(usize),
+    Billboard(usize),
+    Pickup(usize),
+}
+
+// Uniform grid over the play area, rebuilt from scratch every step. Queries
+// return candidates whose boxes touch the query box, in insertion order, so
+// callers see the same order a linear scan would give.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialGrid {
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>, // Entry indices per cell, row-major
+    entries: Vec<(EntityRef, Aabb)>,
+}
+
+impl SpatialGrid {
+    // Empties the grid for a rebuild, sizing it to the canvas the first time
+    pub fn clear(&mut self, canvas_width: f32, canvas_height: f32) {
+        let columns = ((canvas_width + 2.0 * MARGIN) / CELL_SIZE).ceil() as usize;
+        let rows = ((canvas_height + 2.0 * MARGIN) / CELL_SIZE).ceil() as usize;
+        if (columns, rows) != (self.columns, self.rows) {
+            self.columns = columns;
+            self.rows = rows;
+            self.cells = vec![Vec::new(); columns * rows];
+        }
+        self.entries.clear();
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (usize, usize, usize, usize) {
+        let column = |x: f32| (((x + MARGIN) / CELL_SIZE).floor().max(0.0) as usize).min(self.columns - 1);
+        let row = |y: f32| (((y + MARGIN) / CELL_SIZE).floor().max(0.0) as usize).min(self.rows - 1);
+        (column(aabb.x), column(aabb.x + aabb.width), row(aabb.y), row(aabb.y + aabb.height))
+    }
+
+    // Only valid once clear has sized the grid
+    pub fn insert(&mut self, entity: EntityRef, aabb: Aabb) {
+        let index = self.entries.len();
+        self.entries.push((entity, aabb));
+        let (left, right, top, bottom) = self.cell_range(&aabb);
+        for row in top..=bottom {
+            for column in left..=right {
+                self.cells[row * self.columns + column].push(index);
+            }
+        }
+    }
+
+    pub fn query(&self, aabb: &Aabb) -> Vec<EntityRef> {
+        if self.cells.is_empty() {
+            return Vec::new(); // Never built
+        }
+        let (left, right, top, bottom) = self.cell_range(aabb);
+        let mut hits = Vec::new();
+        for row in top..=bottom {
+            for column in left..=right {
+                hits.extend_from_slice(&self.cells[row * self.columns + column]);
+            }
+        }
+        // Big entries sit in several cells
+        hits.sort_unstable();
+        hits.dedup();
+        hits.into_iter()
+            .map(|index| self.entries[index])
+            .filter(|(_, entry)| entry.touches(aabb))
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    // Boxes anywhere from well off the grid to its far side, from specks
+    // to ones spanning many cells
+    fn random_box(rng: &mut Rng) -> Aabb {
+        let mut value = |low: f64, high: f64| (low + rng.next_f64() * (high - low)) as f32;
+        Aabb::new(value(-600.0, 1500.0), value(-500.0, 900.0), value(0.0, 300.0), value(0.0, 150.0))
+    }
+
+    #[test]
+    fn query_matches_a_linear_scan() {
+        let mut rng = Rng::new(22);
+        let mut grid = SpatialGrid::default();
+        for _ in 0..3 {
+            grid.clear(900.0, 330.0);
+            let boxes: Vec<Aabb> = (0..200).map(|_| random_box(&mut rng)).collect();
+            for (i, aabb) in boxes.iter().enumerate() {
+                grid.insert(EntityRef::Obstacle(i), *aabb);
+            }
+
+            for _ in 0..500 {
+                let query = random_box(&mut rng);
+                let expected: Vec<EntityRef> = boxes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, aabb)| aabb.touches(&query))
+                    .map(|(i, _)| EntityRef::Obstacle(i))
+                    .collect();
+                assert_eq!(grid.query(&query), expected, "query {:?}", query);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_boxes_touching_across_a_cell_boundary() {
+        let mut grid = SpatialGrid::default();
+        grid.clear(900.0, 330.0);
+        // Grid lines fall on multiples of CELL_SIZE
+        grid.insert(EntityRef::Vehicle(0), Aabb::new(0.0, 0.0, CELL_SIZE, 10.0));
+        grid.insert(EntityRef::Vehicle(1), Aabb::new(-CELL_SIZE, 0.0, CELL_SIZE, 10.0));
+        assert_eq!(grid.query(&Aabb::new(CELL_SIZE, 10.0, 5.0, 5.0)), [EntityRef::Vehicle(0)]);
+        assert_eq!(grid.query(&Aabb::new(-5.0, -5.0, 5.0, 5.0)), [EntityRef::Vehicle(0), EntityRef::Vehicle(1)]);
+    }
+
+    #[test]
+    fn empty_until_built() {
+        let mut grid = SpatialGrid::default();
+        let everything = Aabb::new(-1000.0, -1000.0, 3000.0, 3000.0);
+        assert!(grid.query(&everything).is_empty());
+
+        grid.clear(900.0, 330.0);
+        grid.insert(EntityRef::Pickup(0), Aabb::new(10.0, 10.0, 5.0, 5.0));
+        assert_eq!(grid.query(&everything), [EntityRef::Pickup(0)]);
+        grid.clear(900.0, 330.0);
+        assert!(grid.query(&everything).is_empty());
+    }
+}
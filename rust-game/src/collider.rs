@@ -0,0 +1,175 @@
+use crate::background::{BackgroundVehicle, Billboard};
+use crate::entities::Obstacle;
+use crate::physics::Aabb;
+use crate::pickups::Pickup;
+
+// Closing speed (px/s) along the contact normal that deals an impact-scaled
+// collider's full damage: full forward thrust into a wall coming the other
+// way at the starting pace. Slower impacts deal proportionally less.
+const FULL_IMPACT_SPEED: f32 = 360.0;
+const BILLBOARD_DAMAGE: f32 = 5.0;
+
+// Which collision layer something is on, as one bit of a LayerMask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerMask(u8);
+
+impl LayerMask {
+    pub const OBSTACLES: LayerMask = LayerMask(1);
+    pub const TRAFFIC: LayerMask = LayerMask(1 << 1);
+    pub const BILLBOARDS: LayerMask = LayerMask(1 << 2);
+    pub const PICKUPS: LayerMask = LayerMask(1 << 3);
+
+    pub const fn union(self, other: LayerMask) -> LayerMask {
+        LayerMask(self.0 | other.0)
+    }
+
+    pub fn intersects(self, other: LayerMask) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+// How much a hit hurts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DamageProfile {
+    Flat(f32),   // The same however it was hit
+    Impact(f32), // Full damage at FULL_IMPACT_SPEED, scaled down for slower hits
+}
+
+impl DamageProfile {
+    pub fn amount(&self, impact_speed: f32) -> f32 {
+        match *self {
+            DamageProfile::Flat(damage) => damage,
+            DamageProfile::Impact(damage) => damage * (impact_speed / FULL_IMPACT_SPEED).min(1.0),
+        }
+    }
+}
+
+// How far a hit throws the cab off the struck face: `seconds` of travel at
+// the impact speed, at least `min` px so it always ends up clear and at most
+// `max` px so a head-on crash doesn't teleport it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KnockbackProfile {
+    pub seconds: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl KnockbackProfile {
+    pub const STANDARD: KnockbackProfile = KnockbackProfile { seconds: 0.15, min: 10.0, max: 60.0 };
+
+    pub fn distance(&self, impact_speed: f32) -> f32 {
+        (impact_speed * self.seconds).clamp(self.min, self.max)
+    }
+}
+
+// What a collider does while the cab's collision cooldown is running. Every
+// damaging hit starts the cooldown, and nothing deals damage during it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IFramePolicy {
+    Solid,       // Still blocks and knocks back
+    PassThrough, // Ignored until the cooldown ends
+}
+
+// Something the cab can run into. The collision pipeline in GameState
+// applies the same rules to every collider from these.
+pub trait Collider {
+    fn shape(&self) -> Aabb;
+
+    // Velocity in px/s, given the foreground scroll everything static rides on
+    fn velocity(&self, scroll: (f32, f32)) -> (f32, f32) {
+        scroll
+    }
+
+    fn layer(&self) -> LayerMask;
+    fn damage_profile(&self) -> DamageProfile;
+
+    fn knockback_profile(&self) -> KnockbackProfile {
+        KnockbackProfile::STANDARD
+    }
+
+    fn i_frame_policy(&self) -> IFramePolicy;
+}
+
+// Buildings and platforms: solid, and they hurt more the harder they're hit
+impl Collider for Obstacle {
+    fn shape(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn layer(&self) -> LayerMask {
+        LayerMask::OBSTACLES
+    }
+
+    fn damage_profile(&self) -> DamageProfile {
+        DamageProfile::Impact(self.damage)
+    }
+
+    fn i_frame_policy(&self) -> IFramePolicy {
+        IFramePolicy::Solid
+    }
+}
+
+// Traffic damage is tuned per archetype, so it stays flat
+impl Collider for BackgroundVehicle {
+    fn shape(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn velocity(&self, _scroll: (f32, f32)) -> (f32, f32) {
+        BackgroundVehicle::velocity(self)
+    }
+
+    fn layer(&self) -> LayerMask {
+        LayerMask::TRAFFIC
+    }
+
+    fn damage_profile(&self) -> DamageProfile {
+        DamageProfile::Flat(self.damage)
+    }
+
+    fn i_frame_policy(&self) -> IFramePolicy {
+        IFramePolicy::PassThrough
+    }
+}
+
+impl Collider for Billboard {
+    fn shape(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn layer(&self) -> LayerMask {
+        LayerMask::BILLBOARDS
+    }
+
+    fn damage_profile(&self) -> DamageProfile {
+        DamageProfile::Flat(BILLBOARD_DAMAGE)
+    }
+
+    fn i_frame_policy(&self) -> IFramePolicy {
+        IFramePolicy::PassThrough
+    }
+}
+
+// Pickups sit on their own layer outside the cab's collision mask: they're
+// swept for collection and never block or knock the cab about
+impl Collider for Pickup {
+    fn shape(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn velocity(&self, _scroll: (f32, f32)) -> (f32, f32) {
+        (self.velocity_x, self.velocity_y)
+    }
+
+    fn layer(&self) -> LayerMask {
+        LayerMask::PICKUPS
+    }
+
+    fn damage_profile(&self) -> DamageProfile {
+        DamageProfile::Flat(0.0)
+    }
+
+    fn i_frame_policy(&self) -> IFramePolicy {
+        IFramePolicy::PassThrough
+    }
+}
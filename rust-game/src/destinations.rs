@@ -0,0 +1,117 @@
+use crate::entities::{Obstacle, ObstacleType};
+use crate::obstacle_archetypes::ObstacleCatalog;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+
+// Scroll distance, in pixels, between a pickup and its destination appearing
+// at the right edge of the screen
+pub const MIN_SPAWN_DISTANCE: f32 = 600.0;
+pub const MAX_SPAWN_DISTANCE: f32 = 1500.0;
+// Obstacles still off-screen this close to the destination are dropped so
+// nothing blocks the approach
+const CLEARANCE: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DestinationKind {
+    Building,
+    FloatingPlatform,
+    Dirigible,
+}
+
+impl DestinationKind {
+    pub const ALL: [DestinationKind; 3] = [
+        DestinationKind::Building,
+        DestinationKind::FloatingPlatform,
+        DestinationKind::Dirigible,
+    ];
+
+    pub fn random(rng: &mut Rng) -> Self {
+        match (rng.next_f64() * 3.0) as u32 {
+            0 => DestinationKind::Building,
+            1 => DestinationKind::FloatingPlatform,
+            _ => DestinationKind::Dirigible,
+        }
+    }
+
+    pub fn obstacle_type(self) -> ObstacleType {
+        match self {
+            DestinationKind::Building => ObstacleType::BuildingTop,
+            DestinationKind::FloatingPlatform => ObstacleType::FloatingPlatform,
+            DestinationKind::Dirigible => ObstacleType::Dirigible,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DestinationKind::Building => "building",
+            DestinationKind::FloatingPlatform => "floating platform",
+            DestinationKind::Dirigible => "dirigible",
+        }
+    }
+}
+
+// A destination that has been promised but not spawned yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledDestination {
+    pub kind: DestinationKind,
+    pub distance: f32, // Pixels left to scroll before it spawns
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Destinations {
+    pub scheduled: Option<ScheduledDestination>,
+}
+
+impl Destinations {
+    pub fn new() -> Self {
+        Destinations::default()
+    }
+
+    pub fn schedule(&mut self, kind: DestinationKind, rng: &mut Rng) {
+        let distance = MIN_SPAWN_DISTANCE + (rng.next_f64() * (MAX_SPAWN_DISTANCE - MIN_SPAWN_DISTANCE) as f64) as f32;
+        self.scheduled = Some(ScheduledDestination { kind, distance });
+    }
+
+    pub fn cancel(&mut self) {
+        self.scheduled = None;
+    }
+
+    pub fn advance(&mut self, scrolled: f32) {
+        if let Some(scheduled) = &mut self.scheduled {
+            scheduled.distance -= scrolled;
+        }
+    }
+
+    // Hands over the destination once its distance has been covered; the
+    // caller must spawn it this step
+    pub fn take_due(&mut self) -> Option<DestinationKind> {
+        match &self.scheduled {
+            Some(scheduled) if scheduled.distance <= 0.0 => self.scheduled.take().map(|s| s.kind),
+            _ => None,
+        }
+    }
+}
+
+// Where a due destination goes: just past whatever is already on screen, so
+// it never waits behind the regular spawner's queue of off-screen obstacles.
+// Those queued obstacles that would crowd it are removed.
+pub fn place(
+    kind: DestinationKind,
+    catalog: &ObstacleCatalog,
+    obstacles: &mut Vec<Obstacle>,
+    canvas_width: f32,
+    rng: &mut Rng,
+) -> Obstacle {
+    let x = obstacles
+        .iter()
+        .filter(|o| o.x < canvas_width)
+        .map(|o| o.x + o.width + CLEARANCE)
+        .fold(canvas_width, f32::max);
+    let archetype = catalog
+        .random_of_type(&kind.obstacle_type(), rng)
+        .expect("validated catalogs cover every destination type");
+    let destination = archetype.spawn(x, rng);
+    let (left, right) = (x - CLEARANCE, x + destination.width + CLEARANCE);
+    obstacles.retain(|o| o.x < canvas_width || o.x + o.width < left || o.x > right);
+    destination
+}
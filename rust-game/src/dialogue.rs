@@ -0,0 +1,99 @@
+use crate::destinations::DestinationKind;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Lines passengers say, by career and by where they're headed
+const BUILTIN_TABLES: &str = include_str!("../data/dialogue.json");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogueError {
+    Parse(String),
+    EmptyTable(&'static str),
+    MissingPlaceholder(String), // Template that never says where the passenger is going
+}
+
+impl fmt::Display for DialogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DialogueError::Parse(reason) => write!(f, "invalid dialogue tables: {}", reason),
+            DialogueError::EmptyTable(table) => write!(f, "dialogue table `{}` is empty", table),
+            DialogueError::MissingPlaceholder(template) => {
+                write!(f, "dialogue template has no {{destination}}: {:?}", template)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DialogueError {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DestinationNames {
+    pub building: Vec<String>,
+    pub floating_platform: Vec<String>,
+    pub dirigible: Vec<String>,
+}
+
+// Templates use {career} and {destination} placeholders. Careers carry their
+// article ("a cloud farmer") and destinations theirs ("the casino blimp").
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueTables {
+    pub templates: Vec<String>,
+    pub careers: Vec<String>,
+    pub destinations: DestinationNames,
+}
+
+impl Default for DialogueTables {
+    fn default() -> Self {
+        DialogueTables::from_json(BUILTIN_TABLES).expect("built-in dialogue tables are valid")
+    }
+}
+
+fn pick<'a>(table: &'a [String], rng: &mut Rng) -> &'a str {
+    &table[(rng.next_f64() * table.len() as f64) as usize % table.len()]
+}
+
+impl DialogueTables {
+    pub fn from_json(json: &str) -> Result<Self, DialogueError> {
+        let tables: DialogueTables = serde_json::from_str(json).map_err(|e| DialogueError::Parse(e.to_string()))?;
+        tables.validate()?;
+        Ok(tables)
+    }
+
+    pub fn validate(&self) -> Result<(), DialogueError> {
+        let tables = [
+            ("templates", &self.templates),
+            ("careers", &self.careers),
+            ("destinations.Building", &self.destinations.building),
+            ("destinations.FloatingPlatform", &self.destinations.floating_platform),
+            ("destinations.Dirigible", &self.destinations.dirigible),
+        ];
+        for (name, table) in tables {
+            if table.is_empty() {
+                return Err(DialogueError::EmptyTable(name));
+            }
+        }
+        if let Some(template) = self.templates.iter().find(|t| !t.contains("{destination}")) {
+            return Err(DialogueError::MissingPlaceholder(template.clone()));
+        }
+        Ok(())
+    }
+
+    fn destination_names(&self, kind: DestinationKind) -> &[String] {
+        match kind {
+            DestinationKind::Building => &self.destinations.building,
+            DestinationKind::FloatingPlatform => &self.destinations.floating_platform,
+            DestinationKind::Dirigible => &self.destinations.dirigible,
+        }
+    }
+
+    // Always draws exactly three numbers, so swapping in different tables
+    // changes what passengers say but not the rest of the seeded world
+    pub fn line(&self, destination: DestinationKind, rng: &mut Rng) -> String {
+        let template = pick(&self.templates, rng);
+        let career = pick(&self.careers, rng);
+        let place = pick(self.destination_names(destination), rng);
+        template.replace("{career}", career).replace("{destination}", place)
+    }
+}
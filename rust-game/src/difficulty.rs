@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+// Foreground scroll speed at the start of a run, in pixels per second
+pub const BASE_SCROLL_SPEED: f32 = 120.0;
+// Pixels scrolled per difficulty level; about half a minute at the base speed
+const LEVEL_DISTANCE: f32 = 3600.0;
+
+// Eases from `start` to `end` over `ramp` pixels of distance, then holds.
+// `exponent` shapes the ramp: 1 is linear, above 1 starts gently.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Curve {
+    pub start: f32,
+    pub end: f32,
+    pub ramp: f32,
+    pub exponent: f32,
+}
+
+impl Curve {
+    pub fn at(&self, distance: f32) -> f32 {
+        let t = (distance / self.ramp).clamp(0.0, 1.0).powf(self.exponent);
+        self.start + (self.end - self.start) * t
+    }
+}
+
+// Tuning for how the run gets harder with distance
+pub struct DifficultyCurves {
+    pub scroll_speed: Curve,      // Pixels per second
+    pub obstacle_density: Curve,  // Divides the gap between obstacle spawns
+    pub traffic_chance: Curve,    // Multiplier on traffic spawn chances
+    pub vehicle_speed: Curve,     // Multiplier on new vehicles' speed
+}
+
+pub const CURVES: DifficultyCurves = DifficultyCurves {
+    scroll_speed: Curve { start: BASE_SCROLL_SPEED, end: 210.0, ramp: 60000.0, exponent: 1.2 },
+    obstacle_density: Curve { start: 1.0, end: 1.8, ramp: 40000.0, exponent: 1.0 },
+    traffic_chance: Curve { start: 1.0, end: 1.6, ramp: 30000.0, exponent: 1.0 },
+    vehicle_speed: Curve { start: 1.0, end: 1.6, ramp: 50000.0, exponent: 1.5 },
+};
+
+// Current point on the curves. The values are cached each step so the HUD
+// and frontend can read them straight out of the state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Difficulty {
+    pub distance: f32, // Foreground pixels scrolled this run
+    pub level: u32,    // Starts at 1
+    pub scroll_speed: f32,
+    pub obstacle_density: f32,
+    pub traffic_chance: f32,
+    pub vehicle_speed: f32,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Difficulty {
+    pub fn new() -> Self {
+        Self::at(0.0)
+    }
+
+    fn at(distance: f32) -> Self {
+        Difficulty {
+            distance,
+            level: 1 + (distance / LEVEL_DISTANCE) as u32,
+            scroll_speed: CURVES.scroll_speed.at(distance),
+            obstacle_density: CURVES.obstacle_density.at(distance),
+            traffic_chance: CURVES.traffic_chance.at(distance),
+            vehicle_speed: CURVES.vehicle_speed.at(distance),
+        }
+    }
+
+    // How much faster the world scrolls than at the start, for parallax layers
+    pub fn pace(&self) -> f32 {
+        self.scroll_speed / BASE_SCROLL_SPEED
+    }
+
+    // Moves along the curves by one step's scroll. Returns the new level when
+    // it goes up.
+    pub fn advance(&mut self, dt: f32) -> Option<u32> {
+        let level = self.level;
+        *self = Self::at(self.distance + self.scroll_speed * dt);
+        (self.level > level).then_some(self.level)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::background::BillboardType;
+use crate::destinations::DestinationKind;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+
+// Foreground pixels scrolled before the city moves on to another district
+const DISTRICT_LENGTH: f32 = 6000.0;
+// Length of the blend zone where both districts' scenery spawns
+const BLEND_DISTANCE: f32 = 1200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum District {
+    RunDown,
+    Upscale, // Cloud apartments
+    Rural,   // Outskirts
+}
+
+pub struct DistrictProfile {
+    pub building_weights: [f32; 3], // Tall, Wide, Medium
+    pub building_gap: f32,          // Multiplier on the space between background buildings
+    pub traffic_density: f32,       // Multiplier on traffic spawn chances
+    pub billboard_density: f32,     // Multiplier on billboard spawn chance
+    pub billboards: &'static [BillboardType],
+    pub sky_color: u32,
+}
+
+const RUN_DOWN: DistrictProfile = DistrictProfile {
+    building_weights: [1.0, 1.0, 1.0],
+    building_gap: 1.0,
+    traffic_density: 1.0,
+    billboard_density: 1.0,
+    billboards: &[
+        BillboardType::FirstBreak,
+        BillboardType::SecondBreak,
+        BillboardType::Security,
+        BillboardType::Security2,
+        BillboardType::SharkMovie,
+    ],
+    sky_color: 0x3a2040,
+};
+
+const UPSCALE: DistrictProfile = DistrictProfile {
+    building_weights: [3.0, 0.5, 1.0],
+    building_gap: 0.7,
+    traffic_density: 1.2,
+    billboard_density: 1.2,
+    billboards: &[BillboardType::Security2, BillboardType::SharkMovie],
+    sky_color: 0x5d8fc9,
+};
+
+const RURAL: DistrictProfile = DistrictProfile {
+    building_weights: [0.0, 1.0, 2.0],
+    building_gap: 2.5,
+    traffic_density: 0.4,
+    billboard_density: 0.5,
+    billboards: &[BillboardType::FirstBreak, BillboardType::SharkMovie],
+    sky_color: 0xc98a4b,
+};
+
+impl District {
+    pub fn profile(self) -> &'static DistrictProfile {
+        match self {
+            District::RunDown => &RUN_DOWN,
+            District::Upscale => &UPSCALE,
+            District::Rural => &RURAL,
+        }
+    }
+
+    // Where a fare's destination is usually found
+    pub fn for_destination(destination: DestinationKind) -> Self {
+        match destination {
+            DestinationKind::Building => District::RunDown,
+            DestinationKind::FloatingPlatform => District::Upscale,
+            DestinationKind::Dirigible => District::Rural,
+        }
+    }
+
+    fn random_other(self, rng: &mut Rng) -> Self {
+        let others: [District; 2] = match self {
+            District::RunDown => [District::Upscale, District::Rural],
+            District::Upscale => [District::RunDown, District::Rural],
+            District::Rural => [District::RunDown, District::Upscale],
+        };
+        others[(rng.next_f64() * 2.0) as usize % 2]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistrictState {
+    pub current: District,
+    pub next: Option<District>, // District being blended into
+    pub progress: f32,          // 0..1 through the blend zone
+    pub distance: f32,          // Pixels scrolled since entering the current district
+}
+
+impl Default for DistrictState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DistrictState {
+    pub fn new() -> Self {
+        DistrictState {
+            current: District::RunDown,
+            next: None,
+            progress: 0.0,
+            distance: 0.0,
+        }
+    }
+
+    // Starts (or redirects) a transition towards `district`
+    pub fn head_to(&mut self, district: District) {
+        match self.next {
+            Some(next) if next == district => {}
+            // Turning back mid-blend: swap ends so the scenery doesn't jump
+            Some(next) if self.current == district => {
+                self.current = next;
+                self.next = Some(district);
+                self.progress = 1.0 - self.progress;
+            }
+            Some(_) => self.next = Some(district),
+            None if self.current != district => {
+                self.next = Some(district);
+                self.progress = 0.0;
+            }
+            None => {}
+        }
+    }
+
+    pub fn advance(&mut self, scrolled: f32, rng: &mut Rng) {
+        self.distance += scrolled;
+        match self.next {
+            Some(next) => {
+                self.progress += scrolled / BLEND_DISTANCE;
+                if self.progress >= 1.0 {
+                    self.current = next;
+                    self.next = None;
+                    self.progress = 0.0;
+                    self.distance = 0.0;
+                }
+            }
+            None if self.distance >= DISTRICT_LENGTH => self.head_to(self.current.random_other(rng)),
+            None => {}
+        }
+    }
+
+    fn blend(&self, value: impl Fn(&DistrictProfile) -> f32) -> f32 {
+        let from = value(self.current.profile());
+        match self.next {
+            Some(next) => from + (value(next.profile()) - from) * self.progress,
+            None => from,
+        }
+    }
+
+    pub fn traffic_density(&self) -> f32 {
+        self.blend(|profile| profile.traffic_density)
+    }
+
+    pub fn billboard_density(&self) -> f32 {
+        self.blend(|profile| profile.billboard_density)
+    }
+
+    // Sky tints of the current and incoming district, with the incoming one's opacity
+    pub fn sky(&self) -> (u32, Option<(u32, f32)>) {
+        let next = self.next.map(|next| (next.profile().sky_color, self.progress));
+        (self.current.profile().sky_color, next)
+    }
+
+    // Profile for a piece of scenery spawning now; inside the blend zone the
+    // incoming district wins more often the further along it is
+    pub fn spawn_profile(&self, rng: &mut Rng) -> &'static DistrictProfile {
+        match self.next {
+            Some(next) if (rng.next_f64() as f32) < self.progress => next.profile(),
+            _ => self.current.profile(),
+        }
+    }
+}
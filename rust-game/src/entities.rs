@@ -1,15 +1,41 @@
 use serde::{Deserialize, Serialize};
+use crate::input::InputState;
+use crate::physics::Aabb;
+
+// Player thrust speeds in pixels per second
+const SPEED_FORWARD: f32 = 240.0;
+const SPEED_BACKWARD: f32 = 150.0;
+const SPEED_CLIMB: f32 = 150.0;
+const SPEED_DIVE: f32 = 240.0;
+const COLLISION_COOLDOWN: f32 = 0.25;
+pub const FUEL_CAPACITY: f32 = 100.0;
+// Fuel per second at full thrust on one axis; both axes burn twice as fast
+const FUEL_BURN: f32 = 1.5;
+const REFUEL_RATE: f32 = 25.0;
+// An empty cab drops at this speed until it lands or leaves the screen
+const SINK_SPEED: f32 = 90.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub x: f32,
     pub y: f32,
+    pub prev_x: f32, // Position before the last step, for render interpolation
+    pub prev_y: f32,
     pub width: f32,
     pub height: f32,
     pub damage: f32,
     pub velocity_x: f32,
     pub velocity_y: f32,
-    pub collision_cooldown: u32, // Frames until next collision can occur
+    pub collision_cooldown: f32, // Seconds until next collision can occur
+    pub docked_on: Option<u32>, // Obstacle the cab is parked on top of
+    pub fuel: f32,
+    pub fuel_capacity: f32,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Player {
@@ -17,86 +43,120 @@ impl Player {
         Player {
             x: 75.0,
             y: 110.0,
+            prev_x: 75.0,
+            prev_y: 110.0,
             width: 40.0,
             height: 20.0,
             damage: 0.0,
             velocity_x: 0.0,
             velocity_y: 0.0,
-            collision_cooldown: 0,
+            collision_cooldown: 0.0,
+            docked_on: None,
+            fuel: FUEL_CAPACITY,
+            fuel_capacity: FUEL_CAPACITY,
         }
     }
 
-    pub fn update(&mut self, input: &InputState) {
+    pub fn update(&mut self, input: &InputState, dt: f32) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+
         // Decrease collision cooldown
-        if self.collision_cooldown > 0 {
-            self.collision_cooldown -= 1;
-        }
+        self.collision_cooldown = (self.collision_cooldown - dt).max(0.0);
 
         // Reset velocity
         self.velocity_x = 0.0;
         self.velocity_y = 0.0;
 
-        // Apply input to velocity
-        if input.right {
-            self.velocity_x += 4.0;
-        }
-        if input.left {
-            self.velocity_x -= 2.5;
+        // Apply input to velocity, scaled by how hard each axis is pushed
+        let horizontal = input.horizontal();
+        let vertical = input.vertical();
+
+        // No thrust once the tank is dry, just a slow drop
+        if self.is_out_of_fuel() {
+            if self.docked_on.is_none() {
+                self.velocity_y = SINK_SPEED;
+                self.y += self.velocity_y * dt;
+            }
+            return;
         }
-        if input.up {
-            self.velocity_y -= 2.5;
+        // Pushing down against a roof isn't thrust
+        let vertical_thrust = if self.docked_on.is_some() && vertical > 0.0 { 0.0 } else { vertical.abs() };
+        self.fuel = (self.fuel - (horizontal.abs() + vertical_thrust) * FUEL_BURN * dt).max(0.0);
+
+        // Climbing takes off; diving while docked just presses on the roof
+        if self.docked_on.is_some() && vertical < 0.0 {
+            self.docked_on = None;
         }
-        if input.down {
-            self.velocity_y += 4.0;
+        self.velocity_x += horizontal * if horizontal > 0.0 { SPEED_FORWARD } else { SPEED_BACKWARD };
+        if self.docked_on.is_none() {
+            self.velocity_y += vertical * if vertical > 0.0 { SPEED_DIVE } else { SPEED_CLIMB };
         }
 
         // Update position
-        self.x += self.velocity_x;
-        self.y += self.velocity_y;
+        self.x += self.velocity_x * dt;
+        self.y += self.velocity_y * dt;
+    }
+
+    pub fn is_out_of_fuel(&self) -> bool {
+        self.fuel <= 0.0
+    }
+
+    pub fn refuel(&mut self, dt: f32) {
+        self.fuel = (self.fuel + REFUEL_RATE * dt).min(self.fuel_capacity);
     }
 
     pub fn can_take_damage(&self) -> bool {
-        self.collision_cooldown == 0
+        self.collision_cooldown <= 0.0
     }
 
     pub fn apply_collision_cooldown(&mut self) {
-        self.collision_cooldown = 15; // 15 frames = ~0.25 seconds at 60fps
+        self.collision_cooldown = COLLISION_COOLDOWN;
+    }
+
+    // Park on top of the obstacle; it carries the cab along until take-off
+    pub fn dock_on(&mut self, obstacle: &Obstacle) {
+        self.y = obstacle.y - self.height;
+        self.velocity_y = 0.0;
+        self.docked_on = Some(obstacle.id);
     }
 
-    pub fn bounce_up(&mut self, obstacle: &Obstacle) {
-        self.y -= 10.0;
-        self.damage += obstacle.damage / 4.0;
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
     }
 
-    pub fn bounce_down(&mut self, obstacle: &Obstacle) {
-        self.x -= 20.0;
-        self.y += 5.0;
-        self.damage += obstacle.damage / 4.0;
+    // What collides; the nose may clip 2px into things, as it always has
+    pub fn hitbox(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width - 2.0, self.height)
     }
 
-    pub fn bounce_left(&mut self, obstacle: &Obstacle) {
-        self.x -= 100.0;
-        self.damage += obstacle.damage;
+    // How far the cab moved this step as seen from something moving at `velocity` (px/s)
+    pub fn relative_motion(&self, velocity: (f32, f32), dt: f32) -> (f32, f32) {
+        (self.x - self.prev_x - velocity.0 * dt, self.y - self.prev_y - velocity.1 * dt)
     }
 
-    pub fn bounce_right(&mut self, obstacle: &Obstacle) {
-        self.x += 10.0;
-        self.damage += obstacle.damage / 2.0;
+    pub fn is_over(&self, obstacle: &Obstacle) -> bool {
+        let centre_x = self.x + self.width / 2.0;
+        centre_x >= obstacle.x && centre_x <= obstacle.x + obstacle.width
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Obstacle {
+    pub id: u32, // Unique within a game, assigned by GameState on spawn
     pub x: f32,
     pub y: f32,
     pub width: f32,
     pub height: f32,
     pub damage: f32,
     pub obstacle_type: ObstacleType,
-    pub image_src: String,
+    pub archetype: String, // Name of the definition it was spawned from
+    pub sprite: Option<String>,
+    pub inset: Inset, // How far the drawn sprite extends past the hitbox
+    pub behaviors: Vec<ObstacleBehavior>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ObstacleType {
     WideTower,
     TallTower,
@@ -108,120 +168,33 @@ pub enum ObstacleType {
     Billboard,
     BuildingTop,
     Orb, // Add little floating orbs
+    FloatingPlatform, // Solid from the sides and below, landable from above
+    FuelPad,          // Rooftop refueling station
+    Dirigible, // Entertainment airship, only spawned as a fare destination
 }
 
-impl Obstacle {
-    pub fn new(obstacle_type: ObstacleType, x: f32) -> Self {
-        match obstacle_type {
-            ObstacleType::WideTower => Obstacle {
-                x,
-                y: 250.0,
-                width: 100.0,
-                height: 80.0,
-                damage: 8.0,
-                obstacle_type,
-                image_src: String::from("./assets/images/buildings/wide_building.png"),
-            },
-            ObstacleType::TallTower => Obstacle {
-                x,
-                y: 150.0,
-                width: 60.0,
-                height: 180.0,
-                damage: 6.0,
-                obstacle_type,
-                image_src: String::from("./assets/images/buildings/tall_building.png"),
-            },
-            ObstacleType::Train => Obstacle {
-                x,
-                y: 200.0,
-                width: 120.0,
-                height: 40.0,
-                damage: 10.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::VehicleLeft => Obstacle {
-                x,
-                y: 180.0,
-                width: 40.0,
-                height: 20.0,
-                damage: 5.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::VehicleRight => Obstacle {
-                x,
-                y: 180.0,
-                width: 40.0,
-                height: 20.0,
-                damage: 5.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::DeliveryLeft => Obstacle {
-                x,
-                y: 170.0,
-                width: 50.0,
-                height: 30.0,
-                damage: 7.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::DeliveryRight => Obstacle {
-                x,
-                y: 170.0,
-                width: 50.0,
-                height: 30.0,
-                damage: 7.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::Billboard => Obstacle {
-                x,
-                y: 80.0,
-                width: 60.0,
-                height: 40.0,
-                damage: 3.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-            ObstacleType::BuildingTop => Obstacle {
-                x,
-                y: 200.0,
-                width: 80.0,
-                height: 130.0,
-                damage: 6.0,
-                obstacle_type,
-                image_src: String::from("./assets/images/buildings/tall_building.png"),
-            },
-            ObstacleType::Orb => Obstacle {
-                x,
-                y: 120.0 + (js_sys::Math::random() * 60.0) as f32, // Random floating height
-                width: 15.0,
-                height: 15.0,
-                damage: 2.0,
-                obstacle_type,
-                image_src: String::from(""),
-            },
-        }
-    }
+// Margins trimmed off a sprite so the hitbox matches what looks solid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inset {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InputState {
-    pub right: bool,
-    pub left: bool,
-    pub up: bool,
-    pub down: bool,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObstacleBehavior {
+    Landable,   // Can be touched down on from above; hurts from the sides and below
+    Refuel,     // Fills the tank while docked
+    PickupSpot, // Passengers may wait on top
 }
 
-impl InputState {
-    pub fn new() -> Self {
-        InputState {
-            right: false,
-            left: false,
-            up: false,
-            down: false,
-        }
+impl Obstacle {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+
+    pub fn has(&self, behavior: ObstacleBehavior) -> bool {
+        self.behaviors.contains(&behavior)
     }
 }
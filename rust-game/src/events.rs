@@ -0,0 +1,83 @@
+use crate::background::VehicleType;
+use crate::destinations::DestinationKind;
+use crate::entities::ObstacleType;
+use crate::physics::GameOverCause;
+use crate::pickups::PickupKind;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Things that happened during a step, for the frontend to react to (sounds,
+// flashes, HUD messages) without diffing state between frames
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    CollidedWithObstacle { obstacle_type: ObstacleType, damage: f32 },
+    HitByVehicle { vehicle_type: VehicleType, damage: f32 },
+    HitBillboard { damage: f32 },
+    Bumped, // Knocked back without a hit: too gentle to hurt, or during the cooldown
+    CooldownStarted { seconds: f32 },
+    Docked { obstacle_type: ObstacleType, hard: bool },
+    TookOff,
+    FuelLow { fuel: f32 },
+    FuelEmpty,
+    RefuelStarted,
+    PickupCollected { kind: PickupKind },
+    PowerUpExpired { kind: PickupKind },
+    ShieldAbsorbed,
+    MultiplierUp { multiplier: u32 },
+    LevelUp { level: u32 },
+    FarePickedUp { destination: DestinationKind },
+    DestinationSpawned { destination: DestinationKind },
+    FareDelivered { destination: DestinationKind, payout: u32 }, // Payout is the points actually added to the score
+    FareFailed,
+    GameOver { cause: GameOverCause },
+    ReplayFinished, // Live input drives the game again
+}
+
+// Cap for when nobody drains the queue, e.g. a headless run that only
+// wants the final state; the oldest events are dropped first
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+    events: VecDeque<GameEvent>,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: GameEvent) {
+        if self.events.len() >= MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_oldest_events_past_the_cap() {
+        let mut queue = EventQueue::default();
+        for multiplier in 0..MAX_QUEUED_EVENTS as u32 + 10 {
+            queue.push(GameEvent::MultiplierUp { multiplier });
+        }
+        let events = queue.drain();
+        assert_eq!(events.len(), MAX_QUEUED_EVENTS);
+        assert_eq!(events[0], GameEvent::MultiplierUp { multiplier: 10 });
+        assert_eq!(events[MAX_QUEUED_EVENTS - 1], GameEvent::MultiplierUp { multiplier: MAX_QUEUED_EVENTS as u32 + 9 });
+    }
+
+    #[test]
+    fn drain_empties_the_queue_in_order() {
+        let mut queue = EventQueue::default();
+        queue.push(GameEvent::FareFailed);
+        queue.push(GameEvent::ReplayFinished);
+        assert_eq!(queue.drain(), [GameEvent::FareFailed, GameEvent::ReplayFinished]);
+        assert_eq!(queue.drain(), []);
+    }
+}
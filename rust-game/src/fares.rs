@@ -0,0 +1,159 @@
+use crate::destinations::DestinationKind;
+use crate::entities::{Obstacle, ObstacleBehavior, Player};
+use crate::events::{EventQueue, GameEvent};
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+
+// How long the cab has to sit docked on a rooftop to board a passenger
+pub const BOARDING_TIME: f32 = 0.5;
+const SPOT_CHANCE: f64 = 0.35;
+const MAX_WAITING: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Passenger {
+    pub fare: u32,     // Base payout, scaled by the score multiplier on delivery
+    pub patience: f32, // Seconds before the passenger stops tipping
+}
+
+impl Passenger {
+    pub fn new(rng: &mut Rng) -> Self {
+        Passenger {
+            fare: 200 + (rng.next_f64() * 300.0) as u32,
+            patience: 8.0 + (rng.next_f64() * 7.0) as f32,
+        }
+    }
+}
+
+// A passenger waiting on top of an obstacle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupSpot {
+    pub obstacle_id: u32,
+    pub passenger: Passenger,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fare {
+    pub passenger: Passenger,
+    pub destination: DestinationKind, // Chosen at pickup
+    pub destination_id: Option<u32>,  // None until the destination has spawned
+    pub line: String,                 // What the passenger said on boarding
+    pub elapsed: f32,
+}
+
+impl Fare {
+    // Full fare plus up to 50% tip for getting there within the passenger's patience
+    pub fn payout(&self) -> u32 {
+        let tip = if self.elapsed < self.passenger.patience {
+            self.passenger.fare as f32 * 0.5 * (1.0 - self.elapsed / self.passenger.patience)
+        } else {
+            0.0
+        };
+        self.passenger.fare + tip as u32
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fares {
+    pub spots: Vec<PickupSpot>,
+    pub active: Option<Fare>,
+    pub boarding_target: Option<u32>, // Obstacle the cab is currently docked on
+    pub boarding_time: f32,
+    pub delivered: u32,
+    pub failed: u32,
+}
+
+impl Fares {
+    pub fn new() -> Self {
+        Fares::default()
+    }
+
+    // Called for every freshly spawned obstacle; may put a passenger on its roof
+    pub fn offer_spot(&mut self, obstacle: &Obstacle, rng: &mut Rng) {
+        if !obstacle.has(ObstacleBehavior::PickupSpot) || self.active.is_some() || self.spots.len() >= MAX_WAITING {
+            return;
+        }
+        if rng.next_f64() < SPOT_CHANCE {
+            self.spots.push(PickupSpot {
+                obstacle_id: obstacle.id,
+                passenger: Passenger::new(rng),
+            });
+        }
+    }
+
+    pub fn is_waiting_at(&self, obstacle_id: u32) -> bool {
+        self.spots.iter().any(|spot| spot.obstacle_id == obstacle_id)
+    }
+
+    // The active fare's destination has spawned as this obstacle
+    pub fn set_destination(&mut self, obstacle_id: u32) {
+        if let Some(fare) = &mut self.active {
+            fare.destination_id = Some(obstacle_id);
+        }
+    }
+
+    // Ends the active fare on arrival. Returns its base payout; GameState
+    // scores it and reports it.
+    pub fn deliver(&mut self) -> Option<u32> {
+        let fare = self.active.take()?;
+        self.delivered += 1;
+        Some(fare.payout())
+    }
+
+    // Advances pickups and the active fare for one step. Returns the kind of
+    // destination a passenger boarding this step wants to go to.
+    pub fn update(
+        &mut self,
+        player: &Player,
+        obstacles: &[Obstacle],
+        dt: f32,
+        rng: &mut Rng,
+        events: &mut EventQueue,
+    ) -> Option<DestinationKind> {
+        // Passengers leave with their rooftop
+        self.spots.retain(|spot| obstacles.iter().any(|o| o.id == spot.obstacle_id));
+
+        let docked = player.docked_on;
+        if docked.is_some() && docked == self.boarding_target {
+            self.boarding_time += dt;
+        } else {
+            self.boarding_target = docked;
+            self.boarding_time = 0.0;
+        }
+        let docked_long_enough = self.boarding_time >= BOARDING_TIME;
+
+        if let Some(fare) = &mut self.active {
+            fare.elapsed += dt;
+        }
+
+        if let Some(fare) = &self.active {
+            // The world only scrolls one way - a missed destination is a lost fare
+            if let Some(destination_id) = fare.destination_id {
+                if !obstacles.iter().any(|o| o.id == destination_id) {
+                    self.active = None;
+                    self.failed += 1;
+                    events.push(GameEvent::FareFailed);
+                }
+            }
+            return None;
+        }
+
+        if docked_long_enough {
+            if let Some(index) = self.spots.iter().position(|spot| Some(spot.obstacle_id) == docked) {
+                let spot = self.spots.remove(index);
+                self.boarding_time = 0.0;
+                let destination = DestinationKind::random(rng);
+                self.active = Some(Fare {
+                    passenger: spot.passenger,
+                    destination,
+                    destination_id: None,
+                    line: String::new(),
+                    elapsed: 0.0,
+                });
+                events.push(GameEvent::FarePickedUp { destination });
+                return Some(destination);
+            }
+        }
+
+        None
+    }
+}
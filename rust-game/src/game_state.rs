@@ -1,8 +1,40 @@
-use crate::entities::{Player, Obstacle, ObstacleType, InputState};
-use crate::physics::Physics;
+use crate::destinations::{self, Destinations};
+use crate::dialogue::DialogueTables;
+use crate::difficulty::Difficulty;
+use crate::districts::District;
+use crate::entities::{Player, Obstacle, ObstacleBehavior};
+use crate::events::{EventQueue, GameEvent};
+use crate::fares::Fares;
+use crate::input::{quantize_axis, Action, Axis, InputSource, InputState};
+use crate::obstacle_archetypes::ObstacleCatalog;
+use crate::physics::{Contact, Landing, Physics, GameOverCause};
+use crate::pickups::{self, PickupKind, Pickups};
 use crate::background::BackgroundManager;
+use crate::broadphase::{EntityRef, SpatialGrid};
+use crate::collider::{Collider, IFramePolicy, LayerMask};
 use serde::{Deserialize, Serialize};
-use js_sys::Math;
+use crate::replay::{InputChange, Replay, ReplayPlayer, ReplayRecorder};
+use crate::rng::Rng;
+use crate::snapshot::{self, SnapshotError};
+use crate::stats::CollisionStats;
+use crate::vehicle_archetypes::VehicleRegistry;
+
+// The simulation always advances in fixed 60Hz steps regardless of display
+// refresh rate; `update` accumulates real frame time and runs as many steps
+// as fit. Speeds are in pixels per second, timers in seconds.
+pub const FIXED_DT: f32 = 1.0 / 60.0;
+// Cap on frame time fed into the accumulator so a backgrounded tab doesn't
+// fast-forward through seconds of gameplay when it regains focus
+const MAX_FRAME_TIME: f32 = 0.25;
+// Fraction of the tank below which the HUD warns and pads spawn more often
+pub const LOW_FUEL: f32 = 0.25;
+const FUEL_PAD_CHANCE_WHEN_LOW: f64 = 0.3;
+// Slack on the broadphase query for how far anything else can move in a
+// step (the fastest chase car covers about 11px at top difficulty)
+const SWEEP_MARGIN: f32 = 16.0;
+// Collision layers the cab runs into, and the ones it collects from
+const CAB_COLLIDES_WITH: LayerMask = LayerMask::OBSTACLES.union(LayerMask::TRAFFIC).union(LayerMask::BILLBOARDS);
+const CAB_COLLECTS: LayerMask = LayerMask::PICKUPS;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
@@ -11,32 +43,85 @@ pub struct GameState {
     pub background: BackgroundManager,
     pub score: u32,
     pub score_multiplier: u32,
-    pub timer: u32,
+    pub timer: u32, // Fixed steps since the multiplier last doubled
     pub game_over: bool,
+    pub game_over_cause: Option<GameOverCause>,
     pub paused: bool,
+    pub frame: u32, // Fixed steps simulated so far
+    pub difficulty: Difficulty,
+    pub collisions: CollisionStats,
+    pub fares: Fares,
+    pub destinations: Destinations,
+    pub pickups: Pickups,
     pub input: InputState,
+    pub seed: u64,
+    rng: Rng,
     canvas_width: f32,
     canvas_height: f32,
     obstacle_spawn_x: f32,
-    obstacle_spawn_timer: u32,
+    obstacle_spawn_timer: f32,
+    next_obstacle_id: u32,
+    accumulator: f32,
+    // The input log covers the run from frame 0, so it travels with the
+    // snapshot and a resumed run can still be exported as a replay
+    #[serde(default)]
+    recorder: Option<ReplayRecorder>,
+    #[serde(skip)]
+    playback: Option<ReplayPlayer>,
+    #[serde(skip)]
+    events: EventQueue,
+    // Rebuilt from the entity lists every step, so never saved
+    #[serde(skip)]
+    broadphase: SpatialGrid,
+    // Session settings rather than game state; survive restarts and snapshot loads but aren't saved
+    #[serde(skip)]
+    dialogue_tables: DialogueTables,
+    #[serde(skip)]
+    obstacle_catalog: ObstacleCatalog,
+    #[serde(skip)]
+    vehicle_registry: VehicleRegistry,
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new_with_seed(seed: u64) -> Self {
+        Self::new_with_catalog(seed, ObstacleCatalog::default())
+    }
+
+    pub fn new_with_catalog(seed: u64, obstacle_catalog: ObstacleCatalog) -> Self {
+        let mut rng = Rng::new(seed);
+        let background = BackgroundManager::new(900.0, &mut rng);
         let mut state = GameState {
             player: Player::new(),
             obstacles: Vec::new(),
-            background: BackgroundManager::new(900.0),
+            background,
             score: 0,
             score_multiplier: 2,
             timer: 0,
             game_over: false,
+            game_over_cause: None,
             paused: false,
+            frame: 0,
+            difficulty: Difficulty::new(),
+            collisions: CollisionStats::default(),
+            fares: Fares::new(),
+            destinations: Destinations::new(),
+            pickups: Pickups::new(),
             input: InputState::new(),
+            seed,
+            rng,
             canvas_width: 900.0,
             canvas_height: 330.0,
             obstacle_spawn_x: 900.0,
-            obstacle_spawn_timer: 100,
+            obstacle_spawn_timer: 1.7,
+            next_obstacle_id: 0,
+            accumulator: 0.0,
+            recorder: None,
+            playback: None,
+            events: EventQueue::default(),
+            broadphase: SpatialGrid::default(),
+            dialogue_tables: DialogueTables::default(),
+            obstacle_catalog,
+            vehicle_registry: VehicleRegistry::default(),
         };
 
         // Initialize some obstacles
@@ -44,138 +129,380 @@ impl GameState {
         state
     }
 
-    pub fn update(&mut self) {
+    // Feed real elapsed time in; returns the interpolation alpha (0..1) of
+    // how far the leftover time is into the next step, for rendering
+    pub fn update(&mut self, dt_ms: f64) -> f32 {
+        if self.game_over || self.paused {
+            self.accumulator = 0.0;
+            return 0.0;
+        }
+
+        // A NaN would stick in the accumulator and freeze the game for good
+        let frame_time = if dt_ms.is_finite() { (dt_ms / 1000.0) as f32 } else { 0.0 };
+        self.accumulator += frame_time.clamp(0.0, MAX_FRAME_TIME);
+        while self.accumulator >= FIXED_DT {
+            self.step();
+            self.accumulator -= FIXED_DT;
+            if self.game_over {
+                self.accumulator = 0.0;
+                break;
+            }
+        }
+
+        self.accumulator / FIXED_DT
+    }
+
+    // Advance exactly one fixed step; headless runs drive the game with this
+    // directly instead of going through the real-time accumulator
+    pub fn step(&mut self) {
+        self.check_playback_finished();
         if self.game_over || self.paused {
             return;
         }
 
-        // Update background layers
-        self.background.update();
+        let dt = FIXED_DT;
+        if let Some(playback) = &mut self.playback {
+            playback.feed(self.frame, &mut self.input);
+        }
+        self.frame += 1;
+
+        // The world speeds up and gets busier with distance
+        if let Some(level) = self.difficulty.advance(dt) {
+            self.events.push(GameEvent::LevelUp { level });
+        }
+
+        // Update background layers; traffic avoids what's near it as of now
+        self.rebuild_broadphase();
+        self.background.update(&self.difficulty, &self.vehicle_registry, &self.broadphase, &mut self.rng, dt);
 
         // Update player
-        self.player.update(&self.input);
+        let was_docked = self.player.docked_on.is_some();
+        let fuel_before = self.player.fuel;
+        self.player.update(&self.input, dt);
+        if was_docked && self.player.docked_on.is_none() {
+            self.events.push(GameEvent::TookOff);
+        }
+        self.check_fuel(fuel_before);
 
         // Update obstacles (move them left)
-        self.update_obstacles();
+        self.update_obstacles(dt);
+
+        // A docked cab rides along with its roof
+        self.update_dock(dt);
+
+        // Fuel pads fill the tank while docked
+        self.update_refueling(dt);
+
+        // Pickups drift and get pulled in before anything is swept
+        self.pickups.update(&self.player, self.difficulty.scroll_speed, self.canvas_width, dt, &mut self.rng);
 
-        // Check collisions
-        self.check_collisions();
+        // Check obstacle, traffic and billboard collisions against where
+        // everything ended up
+        self.rebuild_broadphase();
+        self.check_collisions(dt);
 
-        // Check traffic vehicle collisions with player
-        self.check_traffic_collisions();
+        // Docking on the active fare's destination
+        self.check_arrival();
 
-        // Check billboard collisions with player
-        self.check_billboard_collisions();
+        // Passenger pickup and drop-off
+        self.update_fares(dt);
+
+        // Collectibles and power-up timers
+        self.update_pickups(dt);
 
         // Check game over conditions
-        if Physics::check_game_over(&self.player, self.canvas_width, self.canvas_height) {
+        if let Some(cause) = Physics::check_game_over(&self.player, self.canvas_width, self.canvas_height) {
             self.game_over = true;
+            self.game_over_cause = Some(cause);
+            self.events.push(GameEvent::GameOver { cause });
         }
 
         // Update score
         self.update_score();
 
         // Spawn new obstacles as needed
-        self.spawn_obstacles();
+        self.spawn_obstacles(dt);
+
+        // Also here, as a run that ended won't step again
+        self.check_playback_finished();
     }
 
-    fn update_obstacles(&mut self) {
+    fn update_obstacles(&mut self, dt: f32) {
         for obstacle in &mut self.obstacles {
-            obstacle.x -= 2.0; // Move obstacles left
+            obstacle.x -= self.difficulty.scroll_speed * dt; // Move obstacles left
         }
+        self.destinations.advance(self.difficulty.scroll_speed * dt);
 
         // Remove obstacles that are off-screen
         self.obstacles.retain(|obstacle| obstacle.x + obstacle.width > -100.0);
     }
 
-    fn check_collisions(&mut self) {
-        for obstacle in &self.obstacles {
-            if Physics::check_collision(&self.player, obstacle) {
-                Physics::handle_collision(&mut self.player, obstacle);
-            }
-        }
-    }
-
-    fn check_traffic_collisions(&mut self) {
-        if !self.player.can_take_damage() {
-            return; // Skip collision checks during cooldown
-        }
-
-        for vehicle in &self.background.traffic_vehicles {
-            if vehicle.check_collision_with_player(
-                self.player.x,
-                self.player.y,
-                self.player.width,
-                self.player.height
-            ) {
-                // Apply damage from traffic vehicle
-                self.player.damage += vehicle.damage;
-                self.player.apply_collision_cooldown();
-                
-                // Push player away from vehicle (similar to obstacle collision)
-                if self.player.y < vehicle.y {
-                    self.player.y -= 10.0;
-                } else if self.player.y > vehicle.y {
-                    self.player.y += 5.0;
-                    self.player.x -= 15.0;
-                } else if self.player.x < vehicle.x {
-                    self.player.x -= 25.0;
-                } else if self.player.x > vehicle.x {
-                    self.player.x += 10.0;
+    fn check_fuel(&mut self, fuel_before: f32) {
+        let low = self.player.fuel_capacity * LOW_FUEL;
+        if fuel_before >= low && self.player.fuel < low {
+            self.events.push(GameEvent::FuelLow { fuel: self.player.fuel });
+        }
+        if fuel_before > 0.0 && self.player.is_out_of_fuel() {
+            self.events.push(GameEvent::FuelEmpty);
+        }
+    }
+
+    pub fn is_refueling(&self) -> bool {
+        self.player.docked_on.is_some_and(|id| {
+            self.obstacles.iter().any(|o| o.id == id && o.has(ObstacleBehavior::Refuel))
+        })
+    }
+
+    fn update_refueling(&mut self, dt: f32) {
+        if self.is_refueling() && self.player.fuel < self.player.fuel_capacity {
+            self.player.refuel(dt);
+        }
+    }
+
+    fn update_dock(&mut self, dt: f32) {
+        let Some(id) = self.player.docked_on else {
+            return;
+        };
+        match self.obstacles.iter().find(|o| o.id == id) {
+            Some(roof) => {
+                self.player.x -= self.difficulty.scroll_speed * dt;
+                self.player.y = roof.y - self.player.height;
+                // Taxiing off the edge undocks
+                if !self.player.is_over(roof) {
+                    self.player.docked_on = None;
                 }
-                
-                // Only handle one collision per frame to avoid multiple damage
-                break;
             }
+            None => self.player.docked_on = None,
+        }
+    }
+
+    fn rebuild_broadphase(&mut self) {
+        let grid = &mut self.broadphase;
+        grid.clear(self.canvas_width, self.canvas_height);
+        for (i, obstacle) in self.obstacles.iter().enumerate() {
+            grid.insert(EntityRef::Obstacle(i), obstacle.aabb());
+        }
+        for (i, vehicle) in self.background.traffic_vehicles.iter().enumerate() {
+            grid.insert(EntityRef::Vehicle(i), vehicle.aabb());
+        }
+        for (i, billboard) in self.background.billboards.iter().enumerate() {
+            grid.insert(EntityRef::Billboard(i), billboard.aabb());
+        }
+        for (i, pickup) in self.pickups.items.iter().enumerate() {
+            grid.insert(EntityRef::Pickup(i), pickup.aabb());
         }
     }
 
-    fn check_billboard_collisions(&mut self) {
-        if !self.player.can_take_damage() {
-            return; // Skip collision checks during cooldown
+    // Broadphase candidates the cab could have touched this step, in list
+    // order: everything near its path, with slack for their own movement
+    fn nearby(&self) -> Vec<EntityRef> {
+        let start = self.player.hitbox().offset(self.player.prev_x - self.player.x, self.player.prev_y - self.player.y);
+        let path = self.player.hitbox().union(&start).expanded(SWEEP_MARGIN, SWEEP_MARGIN);
+        self.broadphase.query(&path)
+    }
+
+    fn collider(&self, entity: EntityRef) -> &dyn Collider {
+        match entity {
+            EntityRef::Obstacle(i) => &self.obstacles[i],
+            EntityRef::Vehicle(i) => &self.background.traffic_vehicles[i],
+            EntityRef::Billboard(i) => &self.background.billboards[i],
+            EntityRef::Pickup(i) => &self.pickups.items[i],
         }
+    }
+
+    // Swept contacts this step with the nearby colliders on `layers`, in list order
+    fn contacts(&self, dt: f32, layers: LayerMask) -> impl Iterator<Item = (EntityRef, Contact)> + '_ {
+        let scroll = self.scroll_velocity();
+        self.nearby().into_iter().filter_map(move |entity| {
+            let collider = self.collider(entity);
+            if !layers.intersects(collider.layer()) {
+                return None;
+            }
+            let contact = Physics::sweep_player(&self.player, &collider.shape(), collider.velocity(scroll), dt)?;
+            Some((entity, contact))
+        })
+    }
+
+    // Earliest contact among the colliders the cab can hit right now,
+    // leaving out `handled`. Ties go to the first in list order.
+    fn first_contact(&self, dt: f32, handled: &[EntityRef]) -> Option<(EntityRef, Contact)> {
+        let cooling_down = !self.player.can_take_damage();
+        self.contacts(dt, CAB_COLLIDES_WITH)
+            .filter(|(entity, _)| !handled.contains(entity))
+            .filter(|(entity, _)| !(cooling_down && self.collider(*entity).i_frame_policy() == IFramePolicy::PassThrough))
+            .min_by(|a, b| a.1.time.total_cmp(&b.1.time))
+    }
 
-        for billboard in &self.background.billboards {
-            // Check if player collides with billboard - using same logic as physics.rs
-            let below_the_top = self.player.y + self.player.height > billboard.y;
-            let above_the_bottom = self.player.y < billboard.y + billboard.height;
-            let to_the_left_of_right = self.player.x < billboard.x + billboard.width;
-            let to_the_right_of_left = self.player.width + self.player.x - 2.0 > billboard.x;
+    fn scroll_velocity(&self) -> (f32, f32) {
+        (-self.difficulty.scroll_speed, 0.0)
+    }
 
-            if below_the_top && above_the_bottom && to_the_left_of_right && to_the_right_of_left {
-                // Apply significant damage from billboard collision
-                self.player.damage += 5.0;
-                self.player.apply_collision_cooldown();
-                
-                // Push player away from billboard
-                if self.player.x < billboard.x + billboard.width / 2.0 {
-                    self.player.x -= 20.0; // Push left
-                } else {
-                    self.player.x += 20.0; // Push right
+    // Colliders are handled in the order the cab reached them. Knockback
+    // moves the cab, so each hit is followed by a fresh sweep over the rest.
+    fn check_collisions(&mut self, dt: f32) {
+        let mut handled = Vec::new();
+        while let Some((entity, contact)) = self.first_contact(dt, &handled) {
+            handled.push(entity);
+            if let EntityRef::Obstacle(i) = entity {
+                if Physics::is_landing_on(&self.player, &self.obstacles[i], &contact) {
+                    self.land(i, &contact);
+                    continue;
                 }
-                
-                // Push player down since billboards are at the top
-                self.player.y += 15.0;
-                
-                // Only handle one collision per frame
-                break;
             }
+            self.collide(entity, &contact, dt);
+        }
+    }
+
+    fn land(&mut self, i: usize, contact: &Contact) {
+        self.player.x += contact.rewind_x;
+        self.player.y += contact.rewind_y;
+        let landing = Physics::landing(&self.player, &self.obstacles[i]);
+        self.player.dock_on(&self.obstacles[i]);
+        if let Landing::Hard { damage } = landing {
+            if let Some(damage) = self.take_hit(damage) {
+                let obstacle_type = self.obstacles[i].obstacle_type.clone();
+                self.collisions.record_obstacle(&obstacle_type);
+                self.events.push(GameEvent::CollidedWithObstacle { obstacle_type, damage });
+            }
+        }
+        let obstacle = &self.obstacles[i];
+        self.events.push(GameEvent::Docked {
+            obstacle_type: obstacle.obstacle_type.clone(),
+            hard: landing != Landing::Soft,
+        });
+        if obstacle.has(ObstacleBehavior::Refuel) {
+            self.events.push(GameEvent::RefuelStarted);
+        }
+    }
+
+    // Knocks the cab clear of whatever it struck, then deals that collider's
+    // damage. Contacts that don't count as a hit are only a bump.
+    fn collide(&mut self, entity: EntityRef, contact: &Contact, dt: f32) {
+        let collider = self.collider(entity);
+        let (shape, knockback, damage) = (collider.shape(), collider.knockback_profile(), collider.damage_profile());
+        Physics::resolve(&mut self.player, &shape, contact, &knockback, dt);
+        let Some(damage) = self.take_hit(damage.amount(contact.impact_speed(dt))) else {
+            self.events.push(GameEvent::Bumped);
+            return;
+        };
+        match entity {
+            EntityRef::Obstacle(i) => {
+                let obstacle_type = self.obstacles[i].obstacle_type.clone();
+                self.collisions.record_obstacle(&obstacle_type);
+                self.events.push(GameEvent::CollidedWithObstacle { obstacle_type, damage });
+            }
+            EntityRef::Vehicle(i) => {
+                let vehicle_type = self.background.traffic_vehicles[i].vehicle_type.clone();
+                self.collisions.record_vehicle(&vehicle_type);
+                self.events.push(GameEvent::HitByVehicle { vehicle_type, damage });
+            }
+            EntityRef::Billboard(_) => {
+                self.collisions.record_billboard();
+                self.events.push(GameEvent::HitBillboard { damage });
+            }
+            EntityRef::Pickup(_) => {} // Not in CAB_COLLIDES_WITH
+        }
+    }
+
+    // Damage rules shared by every hit: nothing hurts while the collision
+    // cooldown runs, a damaging hit starts it, and the shield soaks the
+    // damage but not the cooldown. Returns the damage actually taken, or
+    // None if this wasn't a hit at all (harmless, or during the cooldown).
+    fn take_hit(&mut self, amount: f32) -> Option<f32> {
+        if amount <= 0.0 || !self.player.can_take_damage() {
+            return None;
+        }
+        self.player.apply_collision_cooldown();
+        self.events.push(GameEvent::CooldownStarted { seconds: self.player.collision_cooldown });
+        if self.pickups.is_shielded() {
+            self.events.push(GameEvent::ShieldAbsorbed);
+            return Some(0.0);
+        }
+        self.player.damage += amount;
+        Some(amount)
+    }
+
+    fn check_arrival(&mut self) {
+        let Some(fare) = &self.fares.active else {
+            return;
+        };
+        let destination = fare.destination;
+        if fare.destination_id.is_none() || self.player.docked_on != fare.destination_id {
+            return;
+        }
+
+        if let Some(payout) = self.fares.deliver() {
+            let points = payout.saturating_mul(self.score_multiplier);
+            self.score = self.score.saturating_add(points);
+            self.events.push(GameEvent::FareDelivered { destination, payout: points });
+        }
+    }
+
+    // Pickups are collected on contact and never go through the collision
+    // damage path
+    fn update_pickups(&mut self, dt: f32) {
+        for kind in self.pickups.tick_power_ups(dt) {
+            self.events.push(GameEvent::PowerUpExpired { kind });
+        }
+        // Everything the cab passed through this step, not just where it ended up
+        let touched: Vec<_> = self
+            .contacts(dt, CAB_COLLECTS)
+            .filter_map(|(entity, _)| match entity {
+                EntityRef::Pickup(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+        for kind in self.pickups.collect(&touched) {
+            match kind {
+                PickupKind::Repair => self.player.damage = (self.player.damage - pickups::REPAIR_AMOUNT).max(0.0),
+                PickupKind::ScoreBonus => {
+                    let bonus = pickups::SCORE_BONUS.saturating_mul(self.score_multiplier);
+                    self.score = self.score.saturating_add(bonus);
+                }
+                PickupKind::Shield | PickupKind::Magnet => self.pickups.activate(kind),
+            }
+            self.events.push(GameEvent::PickupCollected { kind });
+        }
+    }
+
+    fn update_fares(&mut self, dt: f32) {
+        let boarded = self.fares.update(&self.player, &self.obstacles, dt, &mut self.rng, &mut self.events);
+        if let Some(destination) = boarded {
+            self.destinations.schedule(destination, &mut self.rng);
+            self.background.district.head_to(District::for_destination(destination));
+            let line = self.dialogue_tables.line(destination, &mut self.rng);
+            if let Some(fare) = &mut self.fares.active {
+                fare.line = line;
+            }
+        } else if self.fares.active.is_none() {
+            self.destinations.cancel();
         }
     }
 
     fn update_score(&mut self) {
         if self.timer == 1000 {
-            self.score_multiplier *= 2;
+            // Saturate so long headless runs can't overflow
+            self.score_multiplier = self.score_multiplier.saturating_mul(2);
             self.timer = 0;
+            self.events.push(GameEvent::MultiplierUp { multiplier: self.score_multiplier });
         }
-        self.score += 1 * self.score_multiplier;
+        self.score = self.score.saturating_add(self.score_multiplier);
         self.timer += 1;
     }
 
-    fn spawn_obstacles(&mut self) {
-        self.obstacle_spawn_timer -= 1;
+    fn spawn_obstacles(&mut self, dt: f32) {
+        // A due destination spawns regardless of the timer, so it always
+        // turns up within its scheduled distance
+        if let Some(destination) = self.destinations.take_due() {
+            let obstacle = destinations::place(destination, &self.obstacle_catalog, &mut self.obstacles, self.canvas_width, &mut self.rng);
+            let id = self.push_obstacle(obstacle);
+            self.fares.set_destination(id);
+            self.events.push(GameEvent::DestinationSpawned { destination });
+        }
+
+        self.obstacle_spawn_timer -= dt;
 
-        if self.obstacle_spawn_timer <= 0 {
+        if self.obstacle_spawn_timer <= 0.0 {
             let mut spawn_x = self.canvas_width;
             if !self.obstacles.is_empty() {
                 let rightmost_x = self.obstacles.iter()
@@ -186,46 +513,678 @@ impl GameState {
                 }
             }
 
-            if let Some(obstacle) = self.random_obstacle(spawn_x) {
-                self.obstacles.push(obstacle);
-            }
-            self.obstacle_spawn_timer = (Math::random() * 100.0) as u32 + 50;
+            let obstacle = self.random_obstacle(spawn_x);
+            self.push_obstacle(obstacle);
+            self.obstacle_spawn_timer = ((self.rng.next_f64() * 1.7) as f32 + 0.8) / self.difficulty.obstacle_density;
         }
     }
 
     fn generate_initial_obstacles(&mut self) {
         let mut spawn_x = self.canvas_width;
         for _ in 0..10 {
-            if let Some(obstacle) = self.random_obstacle(spawn_x) {
-                spawn_x += obstacle.width + (Math::random() * 100.0) as f32 + 50.0;
-                self.obstacles.push(obstacle);
-            }
+            let obstacle = self.random_obstacle(spawn_x);
+            spawn_x += obstacle.width + (self.rng.next_f64() * 100.0) as f32 + 50.0;
+            self.push_obstacle(obstacle);
         }
     }
 
-    fn random_obstacle(&self, spawn_x: f32) -> Option<Obstacle> {
-        let rand_val = (Math::random() * 100.0) as u32;
-        let obstacle_type = match rand_val {
-            0..=33 => ObstacleType::WideTower,
-            34..=66 => ObstacleType::TallTower,
-            67..=99 => ObstacleType::BuildingTop,
-            _ => return None,
+    fn push_obstacle(&mut self, mut obstacle: Obstacle) -> u32 {
+        let id = self.next_obstacle_id;
+        obstacle.id = id;
+        self.next_obstacle_id += 1;
+        self.fares.offer_spot(&obstacle, &mut self.rng);
+        self.obstacles.push(obstacle);
+        id
+    }
+
+    fn random_obstacle(&mut self, spawn_x: f32) -> Obstacle {
+        // A running-low cab gets extra chances at a pad
+        let low_fuel = self.player.fuel < self.player.fuel_capacity * LOW_FUEL;
+        let pad = if low_fuel && self.rng.next_f64() < FUEL_PAD_CHANCE_WHEN_LOW {
+            self.obstacle_catalog.random_with(ObstacleBehavior::Refuel, &mut self.rng)
+        } else {
+            None
+        };
+        let archetype = match pad {
+            Some(archetype) => archetype,
+            None => self.obstacle_catalog.random(&mut self.rng),
         };
 
-        Some(Obstacle::new(obstacle_type, spawn_x + (Math::random() * 200.0) as f32))
+        let x = spawn_x + (self.rng.next_f64() * 200.0) as f32;
+        archetype.spawn(x, &mut self.rng)
+    }
+
+    pub fn set_action(&mut self, source: InputSource, action: Action, pressed: bool) {
+        self.apply_input(source, InputChange::Action(action, pressed));
+    }
+
+    pub fn set_axis(&mut self, source: InputSource, axis: Axis, value: f32) {
+        // Quantized up front so recordings hold exactly what was applied
+        self.apply_input(source, InputChange::Axis(axis, quantize_axis(value)));
     }
 
-    pub fn set_input(&mut self, direction: &str, pressed: bool) {
-        match direction {
-            "right" => self.input.right = pressed,
-            "left" => self.input.left = pressed,
-            "up" => self.input.up = pressed,
-            "down" => self.input.down = pressed,
-            _ => {}
+    fn apply_input(&mut self, source: InputSource, change: InputChange) {
+        // Live input is ignored while a replay is driving the game
+        if self.playback.is_some() {
+            return;
+        }
+        change.apply(source, &mut self.input);
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(self.frame, source, change);
         }
     }
 
+    // Each restart draws its seed from the current RNG, so a whole session
+    // (including restarts) is still reproducible from the first seed
     pub fn reset(&mut self) {
-        *self = GameState::new();
+        let seed = self.rng.next_u64();
+        let recording = self.recorder.is_some();
+        self.restart(self.fresh(seed));
+        if recording {
+            self.recorder = Some(ReplayRecorder::new(seed));
+        }
+    }
+
+    // A replay always covers a whole run, so recording restarts the current
+    // game from its seed
+    pub fn start_recording(&mut self) {
+        self.restart(self.fresh(self.seed));
+        self.recorder = Some(ReplayRecorder::new(self.seed));
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    pub fn export_replay(&self) -> Option<Replay> {
+        self.recorder.as_ref().map(|recorder| recorder.finish(self.frame))
+    }
+
+    pub fn load_replay(&mut self, replay: Replay) {
+        let player = ReplayPlayer::new(replay);
+        self.restart(self.fresh(player.seed()));
+        self.playback = Some(player);
+    }
+
+    // Hands control back to live input once every recorded step has run
+    fn check_playback_finished(&mut self) {
+        if self.playback.as_ref().is_some_and(|playback| playback.is_finished(self.frame)) {
+            self.playback = None;
+            self.events.push(GameEvent::ReplayFinished);
+        }
+    }
+
+    // Swaps in a fresh game while keeping session settings
+    fn restart(&mut self, fresh: GameState) {
+        let dialogue_tables = std::mem::take(&mut self.dialogue_tables);
+        let obstacle_catalog = std::mem::take(&mut self.obstacle_catalog);
+        let vehicle_registry = std::mem::take(&mut self.vehicle_registry);
+        *self = fresh;
+        self.dialogue_tables = dialogue_tables;
+        self.obstacle_catalog = obstacle_catalog;
+        self.vehicle_registry = vehicle_registry;
+    }
+
+    // A new run built from this session's obstacle definitions
+    fn fresh(&self, seed: u64) -> GameState {
+        GameState::new_with_catalog(seed, self.obstacle_catalog.clone())
+    }
+
+    // The setters below swap the data files bundled with the crate for ones
+    // a frontend or mod loaded itself, already validated by their from_json.
+    // Replaces the career/destination tables passengers draw their lines from
+    pub fn set_dialogue_tables(&mut self, tables: DialogueTables) {
+        self.dialogue_tables = tables;
+    }
+
+    // Replaces the obstacle definitions. Takes effect for obstacles spawned
+    // from now on; reset for a run built entirely from them.
+    pub fn set_obstacle_catalog(&mut self, catalog: ObstacleCatalog) {
+        self.obstacle_catalog = catalog;
+    }
+
+    // Replaces the traffic definitions; vehicles already on screen keep theirs
+    pub fn set_vehicle_registry(&mut self, registry: VehicleRegistry) {
+        self.vehicle_registry = registry;
+    }
+
+    // The active passenger's line, for the dialogue box
+    pub fn current_dialogue(&self) -> Option<&str> {
+        self.fares.active.as_ref().map(|fare| fare.line.as_str()).filter(|line| !line.is_empty())
+    }
+
+    // Events pushed since the last drain, oldest first
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        self.events.drain()
+    }
+
+    pub fn canvas_size(&self) -> (f32, f32) {
+        (self.canvas_width, self.canvas_height)
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    // A recording in progress is saved with the game and carries on after a
+    // load; playback isn't, so a restored game always takes live input
+    pub fn save_snapshot(&self) -> Vec<u8> {
+        snapshot::save(self)
+    }
+
+    pub fn load_snapshot(&mut self, bytes: &[u8]) -> Result<(), SnapshotError> {
+        let loaded = snapshot::load(bytes)?;
+        self.restart(loaded);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::{BackgroundVehicle, Billboard, BillboardType};
+    use crate::destinations::{DestinationKind, MAX_SPAWN_DISTANCE, MIN_SPAWN_DISTANCE};
+    use crate::difficulty::{BASE_SCROLL_SPEED, CURVES};
+    use crate::fares::{Passenger, PickupSpot, BOARDING_TIME};
+
+    // Weaves up and down with occasional sideways nudges so runs cover
+    // docking, traffic and pickups rather than just falling
+    fn drive(state: &mut GameState, frames: u32) {
+        for _ in 0..frames {
+            let frame = state.frame;
+            if frame.is_multiple_of(45) {
+                state.set_action(InputSource::Keyboard, Action::Up, (frame / 45).is_multiple_of(2));
+            }
+            if frame.is_multiple_of(70) {
+                let value = [0.0, 0.6, -0.4][(frame / 70) as usize % 3];
+                state.set_axis(InputSource::Gamepad, Axis::Horizontal, value);
+            }
+            state.step();
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_game() {
+        let mut a = GameState::new_with_seed(42);
+        let mut b = GameState::new_with_seed(42);
+        drive(&mut a, 600);
+        drive(&mut b, 600);
+
+        assert_eq!(a.frame, b.frame);
+        assert_eq!(a.save_snapshot(), b.save_snapshot());
+        assert_eq!(a.drain_events(), b.drain_events());
+    }
+
+    #[test]
+    fn replay_reproduces_the_recorded_game() {
+        let mut recorded = GameState::new_with_seed(9);
+        recorded.start_recording();
+        drive(&mut recorded, 400);
+        let bytes = recorded.export_replay().expect("recording").to_bytes();
+
+        let mut replayed = GameState::new_with_seed(1);
+        replayed.load_replay(Replay::from_bytes(&bytes).expect("valid replay"));
+        while replayed.frame < recorded.frame {
+            replayed.step();
+        }
+
+        assert_eq!(replayed.score, recorded.score);
+        assert_eq!((replayed.player.x, replayed.player.y), (recorded.player.x, recorded.player.y));
+        assert_eq!(replayed.player.damage, recorded.player.damage);
+
+        // Once the recording runs out live input takes over again
+        replayed.step();
+        assert!(!replayed.is_replaying());
+        assert!(replayed.drain_events().contains(&GameEvent::ReplayFinished));
+    }
+
+    #[test]
+    fn replay_of_a_crashed_run_finishes_through_update() {
+        let frame_ms = 1000.0 / 60.0;
+        // Nobody at the controls, so the cab drops out of the sky
+        let mut recorded = GameState::new_with_seed(4);
+        recorded.start_recording();
+        while !recorded.game_over {
+            recorded.update(frame_ms);
+        }
+        let replay = recorded.export_replay().expect("recording");
+
+        let mut replayed = GameState::new_with_seed(4);
+        replayed.load_replay(replay);
+        let mut finished = 0;
+        for _ in 0..recorded.frame + 60 {
+            replayed.update(frame_ms);
+            finished += replayed.drain_events().iter().filter(|e| **e == GameEvent::ReplayFinished).count();
+        }
+
+        assert!(!replayed.is_replaying());
+        assert_eq!(finished, 1);
+        assert_eq!(replayed.frame, recorded.frame);
+        assert_eq!(replayed.game_over_cause, recorded.game_over_cause);
+    }
+
+    // Nothing in the sky but the cab, which moves 6px right this step
+    fn clear_sky() -> GameState {
+        let mut state = GameState::new_with_seed(25);
+        state.obstacles.clear();
+        state.background.traffic_vehicles.clear();
+        state.background.billboards.clear();
+        state.pickups.items.clear();
+        state.player = Player { prev_x: 200.0, x: 206.0, prev_y: 120.0, y: 120.0, ..Player::new() };
+        state
+    }
+
+    fn wall(state: &mut GameState) -> Obstacle {
+        let mut wall = state.obstacle_catalog.get("WideTower").expect("built in").spawn(0.0, &mut state.rng);
+        wall.x = 240.0;
+        wall.y = 100.0;
+        wall
+    }
+
+    // A car stopped just ahead of the cab
+    fn parked_car(state: &mut GameState) -> BackgroundVehicle {
+        let mut car = BackgroundVehicle::new_right_moving(243.0, &state.vehicle_registry, &mut state.rng);
+        car.y = 118.0;
+        car.speed = 0.0;
+        car
+    }
+
+    fn sweep_step(state: &mut GameState) {
+        state.rebuild_broadphase();
+        state.check_collisions(FIXED_DT);
+    }
+
+    #[test]
+    fn solid_colliders_still_block_during_i_frames() {
+        let mut state = clear_sky();
+        let wall = wall(&mut state);
+        state.obstacles.push(wall.clone());
+        state.player.apply_collision_cooldown();
+        sweep_step(&mut state);
+
+        let hitbox = state.player.hitbox();
+        assert!(hitbox.x + hitbox.width < wall.x);
+        assert_eq!(state.player.damage, 0.0);
+        assert_eq!(state.drain_events(), [GameEvent::Bumped]);
+    }
+
+    #[test]
+    fn pass_through_colliders_are_ignored_during_i_frames() {
+        let mut state = clear_sky();
+        let car = parked_car(&mut state);
+        state.background.traffic_vehicles.push(car);
+        state.background.billboards.push(Billboard {
+            x: 230.0,
+            y: 100.0,
+            width: 84.0,
+            height: 48.0,
+            billboard_type: BillboardType::FirstBreak,
+        });
+        state.player.apply_collision_cooldown();
+        sweep_step(&mut state);
+
+        assert_eq!((state.player.x, state.player.y), (206.0, 120.0));
+        assert_eq!(state.player.damage, 0.0);
+        assert_eq!(state.drain_events(), []);
+    }
+
+    #[test]
+    fn cooldown_stops_a_second_hit() {
+        let mut state = clear_sky();
+        let car = parked_car(&mut state);
+        let damage = car.damage;
+        state.background.traffic_vehicles.push(car);
+        sweep_step(&mut state);
+
+        assert_eq!(state.player.damage, damage);
+        let events = state.drain_events();
+        let vehicle_type = state.background.traffic_vehicles[0].vehicle_type.clone();
+        assert!(events.contains(&GameEvent::HitByVehicle { vehicle_type, damage }));
+        assert!(events.iter().any(|e| matches!(e, GameEvent::CooldownStarted { .. })));
+
+        // Straight back into it while the cooldown runs
+        state.player = Player { prev_x: 200.0, x: 206.0, prev_y: 120.0, y: 120.0, ..state.player.clone() };
+        sweep_step(&mut state);
+        assert_eq!(state.player.damage, damage);
+        assert_eq!(state.drain_events(), []);
+    }
+
+    // Parks the cab on a fresh obstacle of the given archetype, its left edge at x
+    fn docked(state: &mut GameState, archetype: &str, x: f32) {
+        let roof = state.obstacle_catalog.get(archetype).expect("built in").spawn(x, &mut state.rng);
+        state.player.x = x + 10.0;
+        state.player.dock_on(&roof);
+        state.player.docked_on = Some(state.push_obstacle(roof));
+    }
+
+    #[test]
+    fn thrust_drains_fuel_and_warns_on_the_way_down() {
+        let mut state = clear_sky();
+        state.set_axis(InputSource::Gamepad, Axis::Horizontal, 1.0);
+        state.step();
+        let burned = state.player.fuel_capacity - state.player.fuel;
+        assert!(burned > 0.0);
+
+        state.player.fuel = state.player.fuel_capacity * LOW_FUEL;
+        state.drain_events();
+        state.step();
+        assert!(state.drain_events().iter().any(|e| matches!(e, GameEvent::FuelLow { .. })));
+
+        state.player.fuel = burned / 2.0;
+        state.step();
+        assert!(state.player.is_out_of_fuel());
+        assert!(state.drain_events().contains(&GameEvent::FuelEmpty));
+
+        // No thrust left, so the cab only sinks
+        let x = state.player.x;
+        state.step();
+        assert_eq!(state.player.x, x);
+        assert!(state.player.y > state.player.prev_y);
+    }
+
+    #[test]
+    fn fuel_pads_refill_a_docked_cab() {
+        let mut state = clear_sky();
+        docked(&mut state, "FuelPad", 180.0);
+        state.player.fuel = 10.0;
+        assert!(state.is_refueling());
+        for _ in 0..60 {
+            state.step();
+        }
+        assert!(state.player.fuel > 30.0, "{}", state.player.fuel);
+
+        // Other roofs don't
+        let mut state = clear_sky();
+        docked(&mut state, "WideTower", 180.0);
+        state.player.fuel = 10.0;
+        assert!(!state.is_refueling());
+        state.step();
+        assert_eq!(state.player.fuel, 10.0);
+    }
+
+    #[test]
+    fn running_dry_on_a_roof_ends_out_of_fuel() {
+        // Stuck on a roof that carries the cab off the left edge
+        let mut state = clear_sky();
+        docked(&mut state, "WideTower", -10.0);
+        state.player.fuel = 0.0;
+        for _ in 0..600 {
+            if state.game_over {
+                break;
+            }
+            state.step();
+        }
+        assert_eq!(state.game_over_cause, Some(GameOverCause::OutOfFuel));
+    }
+
+    // Parks the cab on a roof with a passenger waiting and steps until
+    // they board
+    fn board(state: &mut GameState) -> DestinationKind {
+        docked(state, "WideTower", 180.0);
+        let obstacle_id = state.player.docked_on.expect("docked");
+        let passenger = Passenger::new(&mut state.rng);
+        state.fares.spots.push(PickupSpot { obstacle_id, passenger });
+        for _ in 0..60 {
+            state.step();
+            let boarded = state.drain_events().into_iter().find_map(|e| match e {
+                GameEvent::FarePickedUp { destination } => Some(destination),
+                _ => None,
+            });
+            if let Some(destination) = boarded {
+                return destination;
+            }
+        }
+        panic!("passenger never boarded");
+    }
+
+    #[test]
+    fn destinations_spawn_within_the_scheduled_distance() {
+        for seed in [3, 17, 40] {
+            let mut state = clear_sky();
+            state.rng = Rng::new(seed);
+            let destination = board(&mut state);
+
+            let mut scrolled = 0.0;
+            loop {
+                // Keep the sky clear so nothing wrecks the cab on the way
+                state.obstacles.clear();
+                state.background.traffic_vehicles.clear();
+                state.step();
+                scrolled += state.difficulty.scroll_speed * FIXED_DT;
+                if state.drain_events().contains(&GameEvent::DestinationSpawned { destination }) {
+                    break;
+                }
+                assert!(scrolled <= MAX_SPAWN_DISTANCE, "seed {}: nothing after {}px", seed, scrolled);
+            }
+            assert!(scrolled >= MIN_SPAWN_DISTANCE, "seed {}: spawned after {}px", seed, scrolled);
+
+            // At the right edge, as the fare's destination
+            let fare = state.fares.active.as_ref().expect("fare");
+            let spawned = state.obstacles.iter().find(|o| Some(o.id) == fare.destination_id).expect("spawned");
+            assert_eq!(spawned.obstacle_type, destination.obstacle_type());
+            assert!(spawned.x >= state.canvas_width);
+        }
+    }
+
+    #[test]
+    fn passengers_only_wait_on_rooftops() {
+        let mut state = clear_sky();
+        state.fares.spots.clear();
+        for i in 0..40 {
+            for name in ["WideTower", "BuildingTop"] {
+                let obstacle = state.obstacle_catalog.get(name).expect("built in").spawn(i as f32 * 100.0, &mut state.rng);
+                state.push_obstacle(obstacle);
+            }
+        }
+        assert!(!state.fares.spots.is_empty());
+        for spot in &state.fares.spots {
+            let roof = state.obstacles.iter().find(|o| o.id == spot.obstacle_id).expect("on screen");
+            assert!(roof.has(ObstacleBehavior::PickupSpot));
+        }
+    }
+
+    #[test]
+    fn boarding_takes_a_moment_docked() {
+        let mut state = clear_sky();
+        docked(&mut state, "WideTower", 180.0);
+        let obstacle_id = state.player.docked_on.expect("docked");
+        let passenger = Passenger::new(&mut state.rng);
+        state.fares.spots.push(PickupSpot { obstacle_id, passenger });
+
+        for _ in 0..(BOARDING_TIME / FIXED_DT) as u32 - 1 {
+            state.step();
+        }
+        assert!(state.fares.active.is_none());
+
+        for _ in 0..5 {
+            if state.fares.active.is_some() {
+                break;
+            }
+            state.step();
+        }
+        let destination = state.fares.active.as_ref().expect("boarded").destination;
+        assert!(!state.fares.is_waiting_at(obstacle_id));
+        assert_eq!(state.destinations.scheduled.as_ref().map(|s| s.kind), Some(destination));
+        assert!(state.drain_events().contains(&GameEvent::FarePickedUp { destination }));
+    }
+
+    #[test]
+    fn docking_on_the_destination_delivers() {
+        let mut state = clear_sky();
+        let destination = board(&mut state);
+        let name = state.obstacle_catalog.random_of_type(&destination.obstacle_type(), &mut state.rng).expect("built in").name.clone();
+        docked(&mut state, &name, 180.0);
+        state.fares.set_destination(state.player.docked_on.expect("docked"));
+
+        let fare = state.fares.active.as_ref().expect("fare");
+        let payout = fare.payout() * state.score_multiplier;
+        let score = state.score;
+        state.step();
+
+        assert!(state.fares.active.is_none());
+        assert_eq!(state.fares.delivered, 1);
+        assert_eq!(state.score, score + payout + state.score_multiplier);
+        assert!(state.drain_events().contains(&GameEvent::FareDelivered { destination, payout }));
+    }
+
+    #[test]
+    fn a_destination_scrolled_away_loses_the_fare() {
+        let mut state = clear_sky();
+        board(&mut state);
+        let roof = state.obstacle_catalog.get("BuildingTop").expect("built in").spawn(300.0, &mut state.rng);
+        let id = state.push_obstacle(roof);
+        state.fares.set_destination(id);
+        state.obstacles.retain(|o| o.id != id);
+        state.step();
+
+        assert!(state.fares.active.is_none());
+        assert_eq!(state.fares.failed, 1);
+        assert!(state.destinations.scheduled.is_none());
+        assert!(state.drain_events().contains(&GameEvent::FareFailed));
+    }
+
+    #[test]
+    fn fares_are_saved_with_the_game() {
+        let mut state = clear_sky();
+        let destination = board(&mut state);
+        let mut restored = GameState::new_with_seed(1);
+        restored.load_snapshot(&state.save_snapshot()).expect("valid snapshot");
+
+        let fare = restored.fares.active.as_ref().expect("fare");
+        assert_eq!(fare.destination, destination);
+        assert_eq!(fare.passenger.fare, state.fares.active.as_ref().expect("fare").passenger.fare);
+        assert_eq!(restored.destinations.scheduled.map(|s| s.kind), Some(destination));
+    }
+
+    // Far enough along that every difficulty curve has levelled off
+    const LATE: f32 = 100_000.0;
+
+    #[test]
+    fn levels_go_up_with_distance() {
+        let mut state = clear_sky();
+        assert_eq!(state.difficulty.level, 1);
+        state.difficulty.distance = 3599.0;
+        state.step();
+        assert_eq!(state.difficulty.level, 2);
+        assert!(state.difficulty.scroll_speed > BASE_SCROLL_SPEED);
+        assert!(state.drain_events().contains(&GameEvent::LevelUp { level: 2 }));
+    }
+
+    #[test]
+    fn obstacles_scroll_at_the_current_speed() {
+        let mut state = clear_sky();
+        state.difficulty.distance = LATE;
+        let wall = wall(&mut state);
+        state.obstacles.push(wall.clone());
+        state.step();
+
+        assert_eq!(state.difficulty.scroll_speed, CURVES.scroll_speed.end);
+        let moved = wall.x - state.obstacles[0].x;
+        assert!((moved - CURVES.scroll_speed.end * FIXED_DT).abs() < 1e-3, "{}", moved);
+    }
+
+    #[test]
+    fn obstacles_spawn_closer_together_later_on() {
+        for (distance, density) in [(0.0, CURVES.obstacle_density.start), (LATE, CURVES.obstacle_density.end)] {
+            let mut state = clear_sky();
+            state.difficulty.distance = distance;
+            state.obstacle_spawn_timer = 0.0;
+            state.step();
+            // The unscaled gap is 0.8 to 2.5 seconds
+            let gap = state.obstacle_spawn_timer * density;
+            assert!((0.8..=2.5).contains(&gap), "{}", gap);
+        }
+    }
+
+    #[test]
+    fn late_traffic_is_faster() {
+        let mut state = clear_sky();
+        state.difficulty.distance = LATE;
+        let slowest = state.vehicle_registry.archetypes.iter().map(|a| a.speed).fold(f32::MAX, f32::min);
+        let mut seen = 0;
+        for _ in 0..600 {
+            state.player.damage = 0.0;
+            state.step();
+            for vehicle in &state.background.traffic_vehicles {
+                assert!(vehicle.speed >= slowest * CURVES.vehicle_speed.end, "{:?}", vehicle);
+            }
+            seen = seen.max(state.background.traffic_vehicles.len());
+        }
+        assert!(seen > 0);
+    }
+
+    #[test]
+    fn update_runs_whole_steps_and_returns_the_leftover() {
+        let mut state = GameState::new_with_seed(2);
+        let alpha = state.update(1500.0 / 60.0);
+        assert_eq!(state.frame, 1);
+        assert!((alpha - 0.5).abs() < 1e-3, "{}", alpha);
+
+        // A bit over another half step completes the second
+        state.update(600.0 / 60.0);
+        assert_eq!(state.frame, 2);
+    }
+
+    #[test]
+    fn update_caps_long_frames() {
+        // A five second hitch only catches up MAX_FRAME_TIME's worth
+        let mut hitched = GameState::new_with_seed(2);
+        hitched.update(5000.0);
+        let mut capped = GameState::new_with_seed(2);
+        capped.update(MAX_FRAME_TIME as f64 * 1000.0);
+        assert_eq!(hitched.frame, capped.frame);
+        assert!(hitched.frame >= 14);
+    }
+
+    #[test]
+    fn update_ignores_non_finite_frame_times() {
+        let mut state = GameState::new_with_seed(2);
+        for dt_ms in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(state.update(dt_ms), 0.0);
+        }
+        assert_eq!(state.frame, 0);
+        state.update(1000.0 / 60.0);
+        assert_eq!(state.frame, 1);
+    }
+
+    #[test]
+    fn refresh_rate_does_not_change_the_game() {
+        // Two seconds of holding thrust at 30, 60 and 144Hz
+        let run = |hz: f64| {
+            let mut state = GameState::new_with_seed(60);
+            state.set_action(InputSource::Keyboard, Action::Up, true);
+            for _ in 0..(2.0 * hz) as u32 {
+                state.update(1000.0 / hz);
+            }
+            state
+        };
+        let reference = run(60.0);
+        assert!(!reference.game_over);
+
+        for hz in [30.0, 144.0] {
+            let mut state = run(hz);
+            // Rounding in the accumulator can leave one step straddling the end
+            assert!(state.frame.abs_diff(reference.frame) <= 1, "{}Hz ran {} steps", hz, state.frame);
+            let mut reference = reference.clone();
+            while state.frame < reference.frame {
+                state.step();
+            }
+            while reference.frame < state.frame {
+                reference.step();
+            }
+            state.accumulator = 0.0;
+            reference.accumulator = 0.0;
+            assert_eq!(state.save_snapshot(), reference.save_snapshot(), "{}Hz", hz);
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_games() {
+        let mut a = GameState::new_with_seed(1);
+        let mut b = GameState::new_with_seed(2);
+        drive(&mut a, 120);
+        drive(&mut b, 120);
+
+        assert_ne!(a.save_snapshot(), b.save_snapshot());
     }
 }
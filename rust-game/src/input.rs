@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Digital controls. Each one pushes its axis fully in one direction.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+// Analog thrust, -1.0..1.0. Positive is right / down, matching screen space.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputSource {
+    Keyboard,
+    Gamepad,
+    Touch,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceInput {
+    pub right: bool,
+    pub left: bool,
+    pub up: bool,
+    pub down: bool,
+    pub horizontal: f32,
+    pub vertical: f32,
+}
+
+impl SourceInput {
+    fn horizontal(&self) -> f32 {
+        self.horizontal + self.right as u8 as f32 - self.left as u8 as f32
+    }
+
+    fn vertical(&self) -> f32 {
+        self.vertical + self.down as u8 as f32 - self.up as u8 as f32
+    }
+}
+
+// Every source keeps its own controls; the player sees them summed and
+// clamped, so a keyboard and a gamepad can be used at the same time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputState {
+    pub keyboard: SourceInput,
+    pub gamepad: SourceInput,
+    pub touch: SourceInput,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Analog values are snapped to 1/127 steps so a replay, which stores them
+// as one signed byte, reproduces live play exactly. NaN and infinities from a
+// misbehaving device read as centred, as clamp would pass NaN through.
+pub fn quantize_axis(value: f32) -> f32 {
+    let value = if value.is_finite() { value } else { 0.0 };
+    (value.clamp(-1.0, 1.0) * 127.0).round() / 127.0
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        InputState {
+            keyboard: SourceInput::default(),
+            gamepad: SourceInput::default(),
+            touch: SourceInput::default(),
+        }
+    }
+
+    fn source_mut(&mut self, source: InputSource) -> &mut SourceInput {
+        match source {
+            InputSource::Keyboard => &mut self.keyboard,
+            InputSource::Gamepad => &mut self.gamepad,
+            InputSource::Touch => &mut self.touch,
+        }
+    }
+
+    pub fn set_action(&mut self, source: InputSource, action: Action, pressed: bool) {
+        let input = self.source_mut(source);
+        match action {
+            Action::Right => input.right = pressed,
+            Action::Left => input.left = pressed,
+            Action::Up => input.up = pressed,
+            Action::Down => input.down = pressed,
+        }
+    }
+
+    pub fn set_axis(&mut self, source: InputSource, axis: Axis, value: f32) {
+        let value = quantize_axis(value);
+        let input = self.source_mut(source);
+        match axis {
+            Axis::Horizontal => input.horizontal = value,
+            Axis::Vertical => input.vertical = value,
+        }
+    }
+
+    pub fn horizontal(&self) -> f32 {
+        (self.keyboard.horizontal() + self.gamepad.horizontal() + self.touch.horizontal()).clamp(-1.0, 1.0)
+    }
+
+    pub fn vertical(&self) -> f32 {
+        (self.keyboard.vertical() + self.gamepad.vertical() + self.touch.vertical()).clamp(-1.0, 1.0)
+    }
+}
@@ -0,0 +1,265 @@
+use crate::destinations::DestinationKind;
+use crate::entities::{Inset, Obstacle, ObstacleBehavior, ObstacleType};
+use crate::render::Sprite;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Every building, sign and hazard the spawner can place, with its hitbox and behaviors
+const BUILTIN_CATALOG: &str = include_str!("../data/obstacles.json");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    Parse(String),
+    NothingToSpawn, // Every spawn weight is zero
+    DuplicateName(String),
+    InvalidArchetype(String, &'static str), // Archetype name and what's wrong with it
+    UnknownSprite(String),
+    MissingDestination(ObstacleType), // Fares need at least one archetype per destination type
+    UnlandableDestination(String), // Archetype of a destination type the cab couldn't dock on
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Parse(reason) => write!(f, "invalid obstacle definitions: {}", reason),
+            CatalogError::NothingToSpawn => write!(f, "no obstacle archetype has a spawn weight"),
+            CatalogError::DuplicateName(name) => write!(f, "obstacle archetype `{}` is defined twice", name),
+            CatalogError::InvalidArchetype(name, reason) => write!(f, "obstacle archetype `{}`: {}", name, reason),
+            CatalogError::UnknownSprite(key) => write!(f, "unknown sprite key {:?}", key),
+            CatalogError::MissingDestination(obstacle_type) => {
+                write!(f, "no obstacle archetype of type {:?}, which fares use as a destination", obstacle_type)
+            }
+            CatalogError::UnlandableDestination(name) => {
+                write!(f, "obstacle archetype `{}` can be a fare destination but isn't Landable", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+// Size and weight rules every spawnable archetype shares, vehicles included;
+// the reason goes into the caller's own InvalidArchetype error
+pub(crate) fn check_body(width: f32, height: f32, damage: f32, spawn_weight: f32) -> Result<(), &'static str> {
+    if !(width > 0.0 && height > 0.0) {
+        return Err("width and height must be positive");
+    }
+    if !(damage >= 0.0 && spawn_weight >= 0.0) {
+        return Err("damage and spawn_weight can't be negative");
+    }
+    Ok(())
+}
+
+// One kind of obstacle. Width and height are the sprite; the hitbox is the
+// sprite minus its inset, and its top spawns somewhere in min_y..=max_y.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleArchetype {
+    pub name: String,
+    pub obstacle_type: ObstacleType,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub inset: Inset,
+    pub min_y: f32,
+    pub max_y: f32,
+    pub damage: f32,
+    #[serde(default)]
+    pub sprite: Option<String>, // Key from render::Sprite; drawn as a plain block without one
+    pub spawn_weight: f32,      // Relative chance in the regular spawner; zero for destination-only
+    #[serde(default)]
+    pub behaviors: Vec<ObstacleBehavior>,
+}
+
+impl ObstacleArchetype {
+    // Always draws exactly one number, for the altitude
+    pub fn spawn(&self, x: f32, rng: &mut Rng) -> Obstacle {
+        let inset = self.inset;
+        Obstacle {
+            id: 0,
+            x: x + inset.left,
+            y: self.min_y + (rng.next_f64() * (self.max_y - self.min_y) as f64) as f32,
+            width: self.width - inset.left - inset.right,
+            height: self.height - inset.top - inset.bottom,
+            damage: self.damage,
+            obstacle_type: self.obstacle_type.clone(),
+            archetype: self.name.clone(),
+            sprite: self.sprite.clone(),
+            inset,
+            behaviors: self.behaviors.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), CatalogError> {
+        let invalid = |reason| Err(CatalogError::InvalidArchetype(self.name.clone(), reason));
+        let inset = self.inset;
+        check_body(self.width, self.height, self.damage, self.spawn_weight).or_else(invalid)?;
+        if inset.left + inset.right >= self.width || inset.top + inset.bottom >= self.height {
+            return invalid("inset leaves no hitbox");
+        }
+        if [inset.left, inset.top, inset.right, inset.bottom].iter().any(|side| *side < 0.0) {
+            return invalid("inset can't be negative");
+        }
+        if self.max_y < self.min_y {
+            return invalid("max_y is below min_y");
+        }
+        if let Some(key) = &self.sprite {
+            if Sprite::from_key(key).is_none() {
+                return Err(CatalogError::UnknownSprite(key.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleCatalog {
+    pub archetypes: Vec<ObstacleArchetype>,
+}
+
+impl Default for ObstacleCatalog {
+    fn default() -> Self {
+        ObstacleCatalog::from_json(BUILTIN_CATALOG).expect("built-in obstacle definitions are valid")
+    }
+}
+
+// Weighted pick; falls back to an even pick when every weight is zero
+fn pick<'a>(candidates: &[&'a ObstacleArchetype], rng: &mut Rng) -> &'a ObstacleArchetype {
+    let weights: Vec<f32> = candidates.iter().map(|a| a.spawn_weight).collect();
+    candidates[rng.weighted_index(&weights)]
+}
+
+impl ObstacleCatalog {
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        let catalog: ObstacleCatalog = serde_json::from_str(json).map_err(|e| CatalogError::Parse(e.to_string()))?;
+        catalog.validate()?;
+        Ok(catalog)
+    }
+
+    pub fn validate(&self) -> Result<(), CatalogError> {
+        for (i, archetype) in self.archetypes.iter().enumerate() {
+            archetype.validate()?;
+            if self.archetypes[..i].iter().any(|a| a.name == archetype.name) {
+                return Err(CatalogError::DuplicateName(archetype.name.clone()));
+            }
+        }
+        if !self.archetypes.iter().any(|a| a.spawn_weight > 0.0) {
+            return Err(CatalogError::NothingToSpawn);
+        }
+        for destination in DestinationKind::ALL {
+            let obstacle_type = destination.obstacle_type();
+            let mut archetypes = self.archetypes.iter().filter(|a| a.obstacle_type == obstacle_type).peekable();
+            if archetypes.peek().is_none() {
+                return Err(CatalogError::MissingDestination(obstacle_type));
+            }
+            // Any of them may be placed as the destination, and fares are
+            // only delivered by docking on top
+            if let Some(archetype) = archetypes.find(|a| !a.behaviors.contains(&ObstacleBehavior::Landable)) {
+                return Err(CatalogError::UnlandableDestination(archetype.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ObstacleArchetype> {
+        self.archetypes.iter().find(|a| a.name == name)
+    }
+
+    // An archetype for the regular spawner, by spawn weight
+    pub fn random(&self, rng: &mut Rng) -> &ObstacleArchetype {
+        let candidates: Vec<_> = self.archetypes.iter().filter(|a| a.spawn_weight > 0.0).collect();
+        pick(&candidates, rng)
+    }
+
+    // An archetype with the given behavior, or None if the catalog has none
+    pub fn random_with(&self, behavior: ObstacleBehavior, rng: &mut Rng) -> Option<&ObstacleArchetype> {
+        let candidates: Vec<_> = self.archetypes.iter().filter(|a| a.behaviors.contains(&behavior)).collect();
+        (!candidates.is_empty()).then(|| pick(&candidates, rng))
+    }
+
+    // An archetype of the given type; validation guarantees one for every destination type
+    pub fn random_of_type(&self, obstacle_type: &ObstacleType, rng: &mut Rng) -> Option<&ObstacleArchetype> {
+        let candidates: Vec<_> = self.archetypes.iter().filter(|a| a.obstacle_type == *obstacle_type).collect();
+        (!candidates.is_empty()).then(|| pick(&candidates, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The built-in catalog with one change applied
+    fn edited(edit: impl FnOnce(&mut ObstacleCatalog)) -> Result<(), CatalogError> {
+        let mut catalog = ObstacleCatalog::default();
+        edit(&mut catalog);
+        catalog.validate()
+    }
+
+    fn first(catalog: &mut ObstacleCatalog) -> &mut ObstacleArchetype {
+        &mut catalog.archetypes[0]
+    }
+
+    fn invalid(name: &str, reason: &'static str) -> Result<(), CatalogError> {
+        Err(CatalogError::InvalidArchetype(name.to_string(), reason))
+    }
+
+    #[test]
+    fn builtin_catalog_is_valid() {
+        assert_eq!(ObstacleCatalog::from_json(BUILTIN_CATALOG).map(|_| ()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        assert!(matches!(ObstacleCatalog::from_json("{\"archetypes\": ["), Err(CatalogError::Parse(_))));
+    }
+
+    #[test]
+    fn rejects_bad_archetypes() {
+        let name = ObstacleCatalog::default().archetypes[0].name.clone();
+
+        assert_eq!(edited(|c| first(c).width = 0.0), invalid(&name, "width and height must be positive"));
+        assert_eq!(edited(|c| first(c).height = f32::NAN), invalid(&name, "width and height must be positive"));
+        assert_eq!(edited(|c| first(c).inset.left = first(c).width), invalid(&name, "inset leaves no hitbox"));
+        assert_eq!(edited(|c| first(c).inset.top = -1.0), invalid(&name, "inset can't be negative"));
+        assert_eq!(edited(|c| first(c).max_y = first(c).min_y - 1.0), invalid(&name, "max_y is below min_y"));
+        assert_eq!(edited(|c| first(c).damage = -1.0), invalid(&name, "damage and spawn_weight can't be negative"));
+        assert_eq!(edited(|c| first(c).spawn_weight = -1.0), invalid(&name, "damage and spawn_weight can't be negative"));
+        assert_eq!(
+            edited(|c| first(c).sprite = Some("buildings/nope".to_string())),
+            Err(CatalogError::UnknownSprite("buildings/nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_names() {
+        let result = edited(|c| {
+            let copy = c.archetypes[0].clone();
+            c.archetypes.push(copy);
+        });
+        assert_eq!(result, Err(CatalogError::DuplicateName(ObstacleCatalog::default().archetypes[0].name.clone())));
+    }
+
+    #[test]
+    fn rejects_a_catalog_with_nothing_to_spawn() {
+        let result = edited(|c| c.archetypes.iter_mut().for_each(|a| a.spawn_weight = 0.0));
+        assert_eq!(result, Err(CatalogError::NothingToSpawn));
+    }
+
+    #[test]
+    fn every_destination_type_needs_a_landable_archetype() {
+        for destination in DestinationKind::ALL {
+            let obstacle_type = destination.obstacle_type();
+
+            let result = edited(|c| c.archetypes.retain(|a| a.obstacle_type != obstacle_type));
+            assert_eq!(result, Err(CatalogError::MissingDestination(obstacle_type.clone())));
+
+            let catalog = ObstacleCatalog::default();
+            let name = &catalog.archetypes.iter().find(|a| a.obstacle_type == obstacle_type).expect("built in").name;
+            let result = edited(|c| {
+                let archetype = c.archetypes.iter_mut().find(|a| a.name == *name).expect("built in");
+                archetype.behaviors.retain(|b| *b != ObstacleBehavior::Landable);
+            });
+            assert_eq!(result, Err(CatalogError::UnlandableDestination(name.clone())));
+        }
+    }
+}
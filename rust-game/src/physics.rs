@@ -1,46 +1,502 @@
-use crate::entities::{Player, Obstacle};
+use crate::collider::KnockbackProfile;
+use crate::entities::{Player, Obstacle, ObstacleBehavior};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GameOverCause {
+    FellBelowCanvas,
+    OutOfFuel, // Ran dry, then dropped or drifted off screen
+    LeftCanvas,
+    Wrecked, // Took too much damage
+}
+
+// Descent speed (px/s) up to which touching down docks without damage.
+// Half a full dive, so analog input can land softly.
+pub const SAFE_LANDING_SPEED: f32 = 120.0;
+const FULL_DIVE_SPEED: f32 = 240.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Landing {
+    Soft,
+    Hard { damage: f32 }, // Still docks, but the impact hurts
+}
+
+// Axis-aligned box in screen space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Aabb {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Aabb { x, y, width, height }
+    }
+
+    // Edges touching counts, so this never misses a pair a stricter test would accept
+    pub fn touches(&self, other: &Aabb) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+
+    pub fn expanded(&self, margin_x: f32, margin_y: f32) -> Aabb {
+        Aabb::new(self.x - margin_x, self.y - margin_y, self.width + 2.0 * margin_x, self.height + 2.0 * margin_y)
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let (left, top) = (self.x.min(other.x), self.y.min(other.y));
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Aabb::new(left, top, right - left, bottom - top)
+    }
+
+    pub fn offset(&self, dx: f32, dy: f32) -> Aabb {
+        Aabb::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+}
+
+// Where a moving box first meets another during a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub time: f32, // Fraction of the step's motion covered at first touch; 0 if it started inside
+    // Unit normal of the struck face, pointing back at the mover: (0, -1)
+    // means it came down onto the top
+    pub normal_x: f32,
+    pub normal_y: f32,
+    // Move that puts the mover back where it first touched; zero when it
+    // started inside, as there's no clean spot to go back to
+    pub rewind_x: f32,
+    pub rewind_y: f32,
+    // The whole relative move that was swept
+    pub motion_x: f32,
+    pub motion_y: f32,
+}
+
+impl Contact {
+    // How fast the mover was closing on the struck face, in px/s
+    pub fn impact_speed(&self, dt: f32) -> f32 {
+        (-(self.motion_x * self.normal_x + self.motion_y * self.normal_y) / dt).max(0.0)
+    }
+}
+
+// How deep one box is inside another, along the axis needing the smallest push
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+    pub depth: f32,
+    // Direction to push the first box out, away from the face it's least deep behind
+    pub normal_x: f32,
+    pub normal_y: f32,
+}
+
+// Entry and exit times along one axis, as fractions of `delta`
+fn slab(start: f32, size: f32, delta: f32, target_start: f32, target_size: f32) -> (f32, f32) {
+    let (near, far) = if delta >= 0.0 {
+        (target_start - (start + size), target_start + target_size - start)
+    } else {
+        (target_start + target_size - start, target_start - (start + size))
+    };
+    if delta != 0.0 {
+        (near / delta, far / delta)
+    } else if start < target_start + target_size && target_start < start + size {
+        (f32::NEG_INFINITY, f32::INFINITY) // Overlapping on this axis the whole step
+    } else {
+        (f32::INFINITY, f32::NEG_INFINITY) // Never overlapping on this axis
+    }
+}
 
 pub struct Physics;
 
 impl Physics {
-    pub fn check_collision(player: &Player, obstacle: &Obstacle) -> bool {
-        let below_the_top = player.y + player.height > obstacle.y;
-        let above_the_bottom = player.y < obstacle.y + obstacle.height;
-        let to_the_left_of_right = player.x < obstacle.x + obstacle.width;
-        let to_the_right_of_left = player.width + player.x - 2.0 > obstacle.x;
+    // Swept AABB test: `moving` travels by (dx, dy) over the step while
+    // `target` stays put. Grazing an edge isn't a contact.
+    pub fn sweep(moving: &Aabb, dx: f32, dy: f32, target: &Aabb) -> Option<Contact> {
+        let (entry_x, exit_x) = slab(moving.x, moving.width, dx, target.x, target.width);
+        let (entry_y, exit_y) = slab(moving.y, moving.height, dy, target.y, target.height);
+        let entry = entry_x.max(entry_y);
+        let exit = exit_x.min(exit_y);
+        if entry >= exit || entry > 1.0 || exit <= 0.0 {
+            return None;
+        }
 
-        below_the_top && above_the_bottom && to_the_left_of_right && to_the_right_of_left
+        if entry < 0.0 {
+            // Already inside at the start; the way out is along the shallowest axis
+            let penetration = Physics::penetration(moving, target)?;
+            return Some(Contact {
+                time: 0.0,
+                normal_x: penetration.normal_x,
+                normal_y: penetration.normal_y,
+                rewind_x: 0.0,
+                rewind_y: 0.0,
+                motion_x: dx,
+                motion_y: dy,
+            });
+        }
+        let (normal_x, normal_y) = if entry_x > entry_y {
+            (-dx.signum(), 0.0)
+        } else {
+            (0.0, -dy.signum())
+        };
+        Some(Contact {
+            time: entry,
+            normal_x,
+            normal_y,
+            rewind_x: -dx * (1.0 - entry),
+            rewind_y: -dy * (1.0 - entry),
+            motion_x: dx,
+            motion_y: dy,
+        })
     }
 
-    pub fn handle_collision(player: &mut Player, obstacle: &Obstacle) {
-        if player.y < obstacle.y {
-            player.bounce_up(obstacle);
-        } else if player.y > obstacle.y {
-            player.bounce_down(obstacle);
-        } else if player.x < obstacle.x {
-            player.bounce_left(obstacle);
-        } else if player.x > obstacle.x {
-            player.bounce_right(obstacle);
+    // Minimum translation out of an overlap; None if the boxes only touch or are apart
+    pub fn penetration(moving: &Aabb, target: &Aabb) -> Option<Penetration> {
+        let from_left = moving.x + moving.width - target.x;
+        let from_right = target.x + target.width - moving.x;
+        let from_above = moving.y + moving.height - target.y;
+        let from_below = target.y + target.height - moving.y;
+        let depth = from_left.min(from_right).min(from_above).min(from_below);
+        if depth <= 0.0 {
+            return None;
         }
+        let (normal_x, normal_y) = if depth == from_above {
+            (0.0, -1.0)
+        } else if depth == from_below {
+            (0.0, 1.0)
+        } else if depth == from_left {
+            (-1.0, 0.0)
+        } else {
+            (1.0, 0.0)
+        };
+        Some(Penetration { depth, normal_x, normal_y })
     }
 
-    pub fn check_game_over(player: &Player, canvas_width: f32, canvas_height: f32) -> bool {
-        // Player fell below canvas
-        if player.y > canvas_height {
-            return true;
+    // Puts the cab back where it touched `target`, pushes it out of any
+    // overlap that's left, then knocks it clear along the contact normal
+    pub fn resolve(player: &mut Player, target: &Aabb, contact: &Contact, knockback: &KnockbackProfile, dt: f32) {
+        player.x += contact.rewind_x;
+        player.y += contact.rewind_y;
+        if let Some(penetration) = Physics::penetration(&player.hitbox(), target) {
+            player.x += penetration.normal_x * penetration.depth;
+            player.y += penetration.normal_y * penetration.depth;
         }
-        
+        let distance = knockback.distance(contact.impact_speed(dt));
+        player.x += contact.normal_x * distance;
+        player.y += contact.normal_y * distance;
+    }
+
+    // The cab's move this step against something that moved at `velocity`
+    // (px/s), worked out relative to it so a fast cab can't pass through
+    // thin obstacles between steps
+    pub fn sweep_player(player: &Player, target: &Aabb, velocity: (f32, f32), dt: f32) -> Option<Contact> {
+        let (dx, dy) = player.relative_motion(velocity, dt);
+        let end = player.hitbox();
+        Physics::sweep(&end.offset(-dx, -dy), dx, dy, target)
+    }
+
+    // Contact with a landable top surface from above, rather than its sides or underside
+    pub fn is_landing_on(player: &Player, obstacle: &Obstacle, contact: &Contact) -> bool {
+        obstacle.has(ObstacleBehavior::Landable) && contact.normal_y < 0.0 && player.is_over(obstacle)
+    }
+
+    // How a touchdown went. Hard landings scale from no damage at the safe
+    // speed up to the old top-bounce damage (a quarter) at a full dive.
+    pub fn landing(player: &Player, obstacle: &Obstacle) -> Landing {
+        let speed = player.velocity_y.max(0.0);
+        if speed <= SAFE_LANDING_SPEED {
+            return Landing::Soft;
+        }
+        let excess = ((speed - SAFE_LANDING_SPEED) / (FULL_DIVE_SPEED - SAFE_LANDING_SPEED)).min(1.0);
+        Landing::Hard { damage: obstacle.damage / 4.0 * excess }
+    }
+
+    pub fn check_game_over(player: &Player, canvas_width: f32, canvas_height: f32) -> Option<GameOverCause> {
+        let fell = player.y > canvas_height;
         // Player went too far left or right
-        if canvas_width - player.x - player.width / 2.0 > canvas_width || 
-           canvas_width - player.x - player.width / 2.0 < 0.0 {
-            return true;
+        let left_canvas = canvas_width - player.x - player.width / 2.0 > canvas_width ||
+            canvas_width - player.x - player.width / 2.0 < 0.0;
+
+        // An empty tank is what ended the run however the cab left, e.g. a
+        // dry cab stuck on a roof that scrolls away
+        if (fell || left_canvas) && player.is_out_of_fuel() {
+            return Some(GameOverCause::OutOfFuel);
+        }
+        if fell {
+            return Some(GameOverCause::FellBelowCanvas);
+        }
+        if left_canvas {
+            return Some(GameOverCause::LeftCanvas);
         }
 
         // Player took too much damage
         if player.damage > 9.0 {
-            return true;
+            return Some(GameOverCause::Wrecked);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Inset, ObstacleType};
+
+    const TARGET: Aabb = Aabb { x: 100.0, y: 100.0, width: 50.0, height: 50.0 };
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn sweep_hits_each_face() {
+        // (start, dx, dy, normal) for a 10px box a third of the way into its move at first touch
+        let cases = [
+            (Aabb::new(80.0, 120.0, 10.0, 10.0), 30.0, 0.0, (-1.0, 0.0)),  // left
+            (Aabb::new(160.0, 120.0, 10.0, 10.0), -30.0, 0.0, (1.0, 0.0)), // right
+            (Aabb::new(120.0, 80.0, 10.0, 10.0), 0.0, 30.0, (0.0, -1.0)),  // top
+            (Aabb::new(120.0, 160.0, 10.0, 10.0), 0.0, -30.0, (0.0, 1.0)), // bottom
+        ];
+        for (start, dx, dy, (normal_x, normal_y)) in cases {
+            let contact = Physics::sweep(&start, dx, dy, &TARGET).expect("hits");
+            assert_close(contact.time, 1.0 / 3.0);
+            assert_eq!((contact.normal_x, contact.normal_y), (normal_x, normal_y));
+            assert_close(contact.rewind_x, -dx * 2.0 / 3.0);
+            assert_close(contact.rewind_y, -dy * 2.0 / 3.0);
+            assert_close(contact.impact_speed(1.0), 30.0);
+
+            // Rewinding from the end of the move leaves it just touching
+            let touching = start.offset(dx + contact.rewind_x, dy + contact.rewind_y);
+            assert!(touching.touches(&TARGET));
+            assert_eq!(Physics::penetration(&touching, &TARGET), None);
+        }
+    }
+
+    #[test]
+    fn sweep_picks_the_face_hit_first_on_a_diagonal() {
+        // Drops past the top edge before it reaches the left one
+        let start = Aabb::new(80.0, 80.0, 10.0, 10.0);
+        let contact = Physics::sweep(&start, 30.0, 40.0, &TARGET).expect("hits");
+        assert_eq!((contact.normal_x, contact.normal_y), (-1.0, 0.0));
+        assert_close(contact.time, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn sweep_catches_thin_targets_a_step_would_skip() {
+        let billboard = Aabb::new(200.0, 0.0, 2.0, 100.0);
+        let cab = Aabb::new(150.0, 40.0, 38.0, 20.0);
+        assert!(!cab.offset(120.0, 0.0).touches(&billboard));
+        let contact = Physics::sweep(&cab, 120.0, 0.0, &billboard).expect("hits");
+        assert_eq!((contact.normal_x, contact.normal_y), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn sweep_misses() {
+        let start = Aabb::new(80.0, 80.0, 10.0, 10.0);
+        // Falls short
+        assert_eq!(Physics::sweep(&start, 5.0, 0.0, &TARGET), None);
+        // Moving away
+        assert_eq!(Physics::sweep(&start, -30.0, 0.0, &TARGET), None);
+        // Passes above
+        assert_eq!(Physics::sweep(&start, 200.0, 0.0, &TARGET), None);
+        // Slides along the top edge without going in
+        assert_eq!(Physics::sweep(&Aabb::new(80.0, 90.0, 10.0, 10.0), 50.0, 0.0, &TARGET), None);
+        // Standing still, apart
+        assert_eq!(Physics::sweep(&start, 0.0, 0.0, &TARGET), None);
+    }
+
+    #[test]
+    fn sweep_started_inside_pushes_out_the_shallowest_way() {
+        // 4px into the top, well inside the sides
+        let start = Aabb::new(120.0, 94.0, 10.0, 10.0);
+        let contact = Physics::sweep(&start, 3.0, 2.0, &TARGET).expect("hits");
+        assert_eq!(contact.time, 0.0);
+        assert_eq!((contact.normal_x, contact.normal_y), (0.0, -1.0));
+        assert_eq!((contact.rewind_x, contact.rewind_y), (0.0, 0.0));
+        assert_eq!((contact.motion_x, contact.motion_y), (3.0, 2.0));
+    }
+
+    #[test]
+    fn penetration_uses_the_shallowest_axis() {
+        // (box, normal): each 3px into one face and deeper into the others
+        let cases = [
+            (Aabb::new(87.0, 110.0, 16.0, 16.0), (-1.0, 0.0)),
+            (Aabb::new(147.0, 110.0, 16.0, 16.0), (1.0, 0.0)),
+            (Aabb::new(110.0, 87.0, 16.0, 16.0), (0.0, -1.0)),
+            (Aabb::new(110.0, 147.0, 16.0, 16.0), (0.0, 1.0)),
+        ];
+        for (moving, (normal_x, normal_y)) in cases {
+            let penetration = Physics::penetration(&moving, &TARGET).expect("overlaps");
+            assert_close(penetration.depth, 3.0);
+            assert_eq!((penetration.normal_x, penetration.normal_y), (normal_x, normal_y));
+        }
+    }
+
+    #[test]
+    fn penetration_into_a_side_near_the_top_is_a_side_hit() {
+        // Above the roof line, but only just clipping the wall
+        let cab = Aabb::new(TARGET.x - 36.0, TARGET.y - 15.0, 38.0, 20.0);
+        let penetration = Physics::penetration(&cab, &TARGET).expect("overlaps");
+        assert_eq!((penetration.normal_x, penetration.normal_y), (-1.0, 0.0));
+        assert_close(penetration.depth, 2.0);
+    }
+
+    #[test]
+    fn penetration_ignores_touching_and_apart() {
+        assert_eq!(Physics::penetration(&Aabb::new(90.0, 110.0, 10.0, 10.0), &TARGET), None);
+        assert_eq!(Physics::penetration(&Aabb::new(0.0, 0.0, 10.0, 10.0), &TARGET), None);
+    }
+
+    fn moved_player(from: (f32, f32), to: (f32, f32)) -> Player {
+        Player { prev_x: from.0, prev_y: from.1, x: to.0, y: to.1, ..Player::new() }
+    }
+
+    #[test]
+    fn resolve_knocks_the_cab_clear_of_each_face() {
+        let dt = 0.1;
+        let knockback = KnockbackProfile::STANDARD;
+        // (from, to, where the cab first touched the face)
+        let cases = [
+            ((50.0, 110.0), (70.0, 110.0), (62.0, 110.0)),    // left
+            ((160.0, 110.0), (140.0, 110.0), (150.0, 110.0)), // right
+            ((110.0, 70.0), (110.0, 90.0), (110.0, 80.0)),    // top
+            ((110.0, 160.0), (110.0, 140.0), (110.0, 150.0)), // bottom
+        ];
+        for (from, to, touch) in cases {
+            let mut player = moved_player(from, to);
+            let contact = Physics::sweep_player(&player, &TARGET, (0.0, 0.0), dt).expect("hits");
+            Physics::resolve(&mut player, &TARGET, &contact, &knockback, dt);
+
+            assert_close(contact.impact_speed(dt), 200.0);
+            let distance = knockback.distance(200.0);
+            assert_close(player.x, touch.0 + contact.normal_x * distance);
+            assert_close(player.y, touch.1 + contact.normal_y * distance);
+            assert_eq!(Physics::penetration(&player.hitbox(), &TARGET), None);
+        }
+    }
+
+    #[test]
+    fn resolve_pushes_out_a_cab_that_started_inside() {
+        // Sitting 4px into the roof without moving
+        let mut player = moved_player((110.0, 84.0), (110.0, 84.0));
+        let contact = Physics::sweep_player(&player, &TARGET, (0.0, 0.0), 1.0 / 60.0).expect("hits");
+        assert_eq!((contact.normal_x, contact.normal_y), (0.0, -1.0));
+        Physics::resolve(&mut player, &TARGET, &contact, &KnockbackProfile::STANDARD, 1.0 / 60.0);
+
+        assert_eq!(player.x, 110.0);
+        assert_close(player.y, TARGET.y - player.height - KnockbackProfile::STANDARD.min);
+    }
+
+    #[test]
+    fn resolve_caps_the_knockback_from_a_head_on_crash() {
+        // A chase car closing at 700px/s on a cab flying into it
+        let dt = 1.0 / 60.0;
+        let knockback = KnockbackProfile::STANDARD;
+        let mut player = moved_player((40.0, 110.0), (45.0, 110.0));
+        let car = Aabb::new(80.0, 105.0, 40.0, 20.0);
+        let contact = Physics::sweep_player(&player, &car, (-400.0, 0.0), dt).expect("hits");
+        assert!(contact.impact_speed(dt) * knockback.seconds > knockback.max);
+
+        let touching = player.x + contact.rewind_x;
+        Physics::resolve(&mut player, &car, &contact, &knockback, dt);
+        assert_close(player.x, touching - knockback.max);
+        assert!(player.x - player.prev_x >= -knockback.max);
+    }
+
+    #[test]
+    fn resolve_works_relative_to_a_moving_target() {
+        // The cab hovers while a vehicle drives into its right side
+        let dt = 0.1;
+        let mut player = moved_player((170.0, 110.0), (170.0, 110.0));
+        let vehicle = TARGET.offset(30.0, 0.0);
+        let contact = Physics::sweep_player(&player, &vehicle, (200.0, 0.0), dt).expect("hits");
+        assert_eq!((contact.normal_x, contact.normal_y), (1.0, 0.0));
+        Physics::resolve(&mut player, &vehicle, &contact, &KnockbackProfile::STANDARD, dt);
+        assert!(player.x >= vehicle.x + vehicle.width + KnockbackProfile::STANDARD.min);
+    }
+
+    fn roof(behaviors: Vec<ObstacleBehavior>) -> Obstacle {
+        Obstacle {
+            id: 0,
+            x: TARGET.x,
+            y: TARGET.y,
+            width: TARGET.width,
+            height: TARGET.height,
+            damage: 8.0,
+            obstacle_type: ObstacleType::WideTower,
+            archetype: "WideTower".to_string(),
+            sprite: None,
+            inset: Inset::default(),
+            behaviors,
         }
+    }
+
+    // A cab dropping 15px onto the roof at `fall_speed`, its centre `centre_x`
+    fn descending(centre_x: f32, fall_speed: f32) -> Player {
+        let x = centre_x - Player::new().width / 2.0;
+        Player { velocity_y: fall_speed, ..moved_player((x, 70.0), (x, 85.0)) }
+    }
+
+    fn landing_contact(player: &Player, obstacle: &Obstacle) -> Contact {
+        Physics::sweep_player(player, &obstacle.aabb(), (0.0, 0.0), 1.0 / 60.0).expect("hits")
+    }
+
+    #[test]
+    fn gentle_touchdown_is_a_soft_landing() {
+        let roof = roof(vec![ObstacleBehavior::Landable]);
+        let player = descending(125.0, SAFE_LANDING_SPEED);
+        assert!(Physics::is_landing_on(&player, &roof, &landing_contact(&player, &roof)));
+        assert_eq!(Physics::landing(&player, &roof), Landing::Soft);
+    }
+
+    #[test]
+    fn hard_landing_damage_scales_with_speed() {
+        let roof = roof(vec![ObstacleBehavior::Landable]);
+        // Halfway from the safe speed to a full dive: half of a quarter of the damage
+        let player = descending(125.0, (SAFE_LANDING_SPEED + FULL_DIVE_SPEED) / 2.0);
+        assert!(Physics::is_landing_on(&player, &roof, &landing_contact(&player, &roof)));
+        assert_eq!(Physics::landing(&player, &roof), Landing::Hard { damage: 1.0 });
+        // Diving faster doesn't hurt more than a full dive
+        assert_eq!(Physics::landing(&descending(125.0, 2.0 * FULL_DIVE_SPEED), &roof), Landing::Hard { damage: 2.0 });
+    }
+
+    #[test]
+    fn only_landable_roofs_take_a_landing() {
+        let roof = roof(Vec::new());
+        let player = descending(125.0, 60.0);
+        assert!(!Physics::is_landing_on(&player, &roof, &landing_contact(&player, &roof)));
+    }
+
+    #[test]
+    fn hitting_a_wall_is_not_a_landing() {
+        let roof = roof(vec![ObstacleBehavior::Landable]);
+        let player = moved_player((50.0, 110.0), (70.0, 110.0));
+        let contact = landing_contact(&player, &roof);
+        assert_eq!((contact.normal_x, contact.normal_y), (-1.0, 0.0));
+        assert!(!Physics::is_landing_on(&player, &roof, &contact));
+    }
+
+    #[test]
+    fn overhanging_the_edge_is_not_a_landing() {
+        // Clips the roof from above, but the cab's centre is past the edge
+        let roof = roof(vec![ObstacleBehavior::Landable]);
+        let player = descending(TARGET.x - 5.0, 60.0);
+        let contact = landing_contact(&player, &roof);
+        assert_eq!((contact.normal_x, contact.normal_y), (0.0, -1.0));
+        assert!(!Physics::is_landing_on(&player, &roof, &contact));
+    }
+
+    #[test]
+    fn an_empty_tank_explains_any_exit() {
+        let dry = |x, y| Player { x, y, fuel: 0.0, ..Player::new() };
+        let fueled = |x, y| Player { x, y, ..Player::new() };
 
-        false
+        assert_eq!(Physics::check_game_over(&dry(-30.0, 100.0), 400.0, 300.0), Some(GameOverCause::OutOfFuel));
+        assert_eq!(Physics::check_game_over(&dry(100.0, 310.0), 400.0, 300.0), Some(GameOverCause::OutOfFuel));
+        assert_eq!(Physics::check_game_over(&dry(100.0, 100.0), 400.0, 300.0), None);
+        assert_eq!(Physics::check_game_over(&fueled(-30.0, 100.0), 400.0, 300.0), Some(GameOverCause::LeftCanvas));
+        assert_eq!(Physics::check_game_over(&fueled(100.0, 310.0), 400.0, 300.0), Some(GameOverCause::FellBelowCanvas));
     }
 }
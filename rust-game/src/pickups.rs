@@ -0,0 +1,173 @@
+use crate::entities::Player;
+use crate::physics::Aabb;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+
+const PICKUP_SIZE: f32 = 16.0;
+// Seconds between spawns, picked uniformly from this range
+const MIN_SPAWN_GAP: f32 = 3.0;
+const MAX_SPAWN_GAP: f32 = 7.0;
+// Pickups float in the flight band between the billboards and the rooftops
+const MIN_SPAWN_Y: f32 = 60.0;
+const MAX_SPAWN_Y: f32 = 220.0;
+const SHIELD_SECONDS: f32 = 8.0;
+const MAGNET_SECONDS: f32 = 10.0;
+const MAGNET_RADIUS: f32 = 200.0;
+const MAGNET_PULL: f32 = 300.0; // Pixels per second towards the cab
+pub const REPAIR_AMOUNT: f32 = 3.0;
+pub const SCORE_BONUS: u32 = 500; // Scaled by the score multiplier
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickupKind {
+    Repair,
+    Shield,
+    ScoreBonus,
+    Magnet,
+}
+
+// Relative spawn weights
+const SPAWN_TABLE: [(PickupKind, f32); 4] = [
+    (PickupKind::Repair, 35.0),
+    (PickupKind::Shield, 20.0),
+    (PickupKind::ScoreBonus, 30.0),
+    (PickupKind::Magnet, 15.0),
+];
+
+impl PickupKind {
+    fn random(rng: &mut Rng) -> Self {
+        SPAWN_TABLE[rng.weighted_index(&SPAWN_TABLE.map(|(_, weight)| weight))].0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pickup {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub kind: PickupKind,
+    // Pixels per second over the last step, scroll plus magnet pull, so
+    // collection can sweep the cab against it
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+impl Pickup {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+// Seconds left on each timed power-up; zero when inactive
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerUps {
+    pub shield: f32,
+    pub magnet: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pickups {
+    pub items: Vec<Pickup>,
+    pub power_ups: PowerUps,
+    pub spawn_timer: f32,
+    pub collected: u32,
+}
+
+impl Default for Pickups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pickups {
+    pub fn new() -> Self {
+        Pickups {
+            items: Vec::new(),
+            power_ups: PowerUps::default(),
+            spawn_timer: MIN_SPAWN_GAP,
+            collected: 0,
+        }
+    }
+
+    pub fn is_shielded(&self) -> bool {
+        self.power_ups.shield > 0.0
+    }
+
+    pub fn has_magnet(&self) -> bool {
+        self.power_ups.magnet > 0.0
+    }
+
+    // Starts the timer for timed kinds; instant ones are applied by GameState
+    pub fn activate(&mut self, kind: PickupKind) {
+        match kind {
+            PickupKind::Shield => self.power_ups.shield = SHIELD_SECONDS,
+            PickupKind::Magnet => self.power_ups.magnet = MAGNET_SECONDS,
+            PickupKind::Repair | PickupKind::ScoreBonus => {}
+        }
+    }
+
+    // Counts down power-ups; returns the ones that ran out this step
+    pub fn tick_power_ups(&mut self, dt: f32) -> Vec<PickupKind> {
+        let mut expired = Vec::new();
+        for (kind, timer) in [
+            (PickupKind::Shield, &mut self.power_ups.shield),
+            (PickupKind::Magnet, &mut self.power_ups.magnet),
+        ] {
+            if *timer > 0.0 {
+                *timer = (*timer - dt).max(0.0);
+                if *timer == 0.0 {
+                    expired.push(kind);
+                }
+            }
+        }
+        expired
+    }
+
+    // Scrolls, attracts and spawns pickups for one step. Collection is swept
+    // by GameState along with everything else the cab can touch.
+    pub fn update(&mut self, player: &Player, scroll_speed: f32, canvas_width: f32, dt: f32, rng: &mut Rng) {
+        let magnet = self.has_magnet();
+        let (centre_x, centre_y) = (player.x + player.width / 2.0, player.y + player.height / 2.0);
+        for item in &mut self.items {
+            let (start_x, start_y) = (item.x, item.y);
+            item.x -= scroll_speed * dt;
+            if magnet {
+                let dx = centre_x - (item.x + item.width / 2.0);
+                let dy = centre_y - (item.y + item.height / 2.0);
+                let distance = (dx * dx + dy * dy).sqrt();
+                if distance > 0.0 && distance < MAGNET_RADIUS {
+                    let step = (MAGNET_PULL * dt).min(distance);
+                    item.x += dx / distance * step;
+                    item.y += dy / distance * step;
+                }
+            }
+            item.velocity_x = (item.x - start_x) / dt;
+            item.velocity_y = (item.y - start_y) / dt;
+        }
+        self.items.retain(|item| item.x + item.width > -50.0);
+
+        self.spawn_timer -= dt;
+        if self.spawn_timer <= 0.0 {
+            self.items.push(Pickup {
+                x: canvas_width + 20.0,
+                y: MIN_SPAWN_Y + (rng.next_f64() * (MAX_SPAWN_Y - MIN_SPAWN_Y) as f64) as f32,
+                width: PICKUP_SIZE,
+                height: PICKUP_SIZE,
+                kind: PickupKind::random(rng),
+                velocity_x: -scroll_speed,
+                velocity_y: 0.0,
+            });
+            self.spawn_timer = MIN_SPAWN_GAP + (rng.next_f64() * (MAX_SPAWN_GAP - MIN_SPAWN_GAP) as f64) as f32;
+        }
+    }
+
+    // Removes the items at `indices` (ascending) and returns their kinds in that order
+    pub fn collect(&mut self, indices: &[usize]) -> Vec<PickupKind> {
+        let kinds: Vec<_> = indices.iter().map(|&i| self.items[i].kind).collect();
+        for &i in indices.iter().rev() {
+            self.items.remove(i);
+        }
+        self.collected += kinds.len() as u32;
+        kinds
+    }
+}
@@ -0,0 +1,578 @@
+use crate::background::{BackgroundVehicle, BillboardType, Building, BuildingType, VehicleType};
+use crate::entities::{Obstacle, ObstacleBehavior, ObstacleType};
+use crate::game_state::{GameState, FIXED_DT, LOW_FUEL};
+use crate::pickups::{Pickup, PickupKind};
+use std::fmt::{self, Write};
+
+// Everything the frontend needs to draw a frame, already resolved to image
+// keys, screen rects and z-order. Internal state layout can change freely as
+// long as this output stays the same.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Sky,
+    DistantBuildings,
+    FarBuildings,
+    Traffic,
+    Billboards,
+    Obstacles,
+    Pickups,
+    Player,
+    Hud,
+}
+
+impl Layer {
+    // What a command on this layer draws unless it says otherwise
+    fn entity(self) -> EntityKind {
+        match self {
+            Layer::Sky => EntityKind::Sky,
+            Layer::DistantBuildings | Layer::FarBuildings => EntityKind::Building,
+            Layer::Traffic => EntityKind::Vehicle,
+            Layer::Billboards => EntityKind::Billboard,
+            Layer::Obstacles => EntityKind::Obstacle,
+            Layer::Pickups => EntityKind::Pickup,
+            Layer::Player => EntityKind::Player,
+            Layer::Hud => EntityKind::Hud,
+        }
+    }
+}
+
+// What a command belongs to, so the frontend can tell entities apart
+// without knowing the layer order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Sky,
+    Building,
+    Vehicle,
+    Billboard,
+    Obstacle,
+    FareMarker,
+    Pickup,
+    Player,
+    Hud,
+}
+
+// Image keys are paths under assets/images without the .png extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sprite {
+    TallBuilding,
+    WideBuilding,
+    Taxi,
+    Police,
+    Civil,
+    DeliveryTruck,
+    TrainFront,
+    TrainCenter,
+    TrainBack,
+    Orb,
+    FirstBreak,
+    SecondBreak,
+    Security,
+    Security2,
+    SharkMovie,
+    SkyCondo,
+    SushiBar,
+    MoodySkyCondo,
+    ClosedCraffiti,
+    Drone,
+}
+
+impl Sprite {
+    pub const ALL: [Sprite; 20] = [
+        Sprite::TallBuilding,
+        Sprite::WideBuilding,
+        Sprite::Taxi,
+        Sprite::Police,
+        Sprite::Civil,
+        Sprite::DeliveryTruck,
+        Sprite::TrainFront,
+        Sprite::TrainCenter,
+        Sprite::TrainBack,
+        Sprite::Orb,
+        Sprite::FirstBreak,
+        Sprite::SecondBreak,
+        Sprite::Security,
+        Sprite::Security2,
+        Sprite::SharkMovie,
+        Sprite::SkyCondo,
+        Sprite::SushiBar,
+        Sprite::MoodySkyCondo,
+        Sprite::ClosedCraffiti,
+        Sprite::Drone,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Sprite::TallBuilding => "buildings/tall_building",
+            Sprite::WideBuilding => "buildings/wide_building",
+            Sprite::Taxi => "vehicles/taxi",
+            Sprite::Police => "vehicles/police",
+            Sprite::Civil => "vehicles/civil",
+            Sprite::DeliveryTruck => "vehicles/delivery_truck",
+            Sprite::TrainFront => "vehicles/train_front",
+            Sprite::TrainCenter => "vehicles/train_center",
+            Sprite::TrainBack => "vehicles/train_back",
+            Sprite::Orb => "vehicles/boom",
+            Sprite::FirstBreak => "billboards/first_break",
+            Sprite::SecondBreak => "billboards/second_break",
+            Sprite::Security => "billboards/security",
+            Sprite::Security2 => "billboards/security2",
+            Sprite::SharkMovie => "billboards/shark_movie",
+            Sprite::SkyCondo => "platforms/sky_condo",
+            Sprite::SushiBar => "platforms/sushi_bar",
+            Sprite::MoodySkyCondo => "platforms/moody_sky_condo",
+            Sprite::ClosedCraffiti => "platforms/closed_craffiti",
+            Sprite::Drone => "platforms/drone",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Sprite> {
+        Sprite::ALL.into_iter().find(|sprite| sprite.key() == key)
+    }
+
+    // Position in Sprite::ALL, used as the id in the render buffer
+    pub fn id(self) -> u32 {
+        self as u32
+    }
+}
+
+// Where a text command's string sits in DrawList::text, in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextSpan {
+    pub start: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawKind {
+    Sprite(Sprite),
+    Fill,
+    Text(TextSpan),
+}
+
+// Sprites fill the rect; fills paint it with `color`; text is drawn at
+// (x, y) with a font `height` pixels tall in `color`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawCommand {
+    pub layer: Layer,
+    pub entity: EntityKind,
+    pub kind: DrawKind,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub flip_x: bool,         // Sprite is mirrored horizontally
+    pub alpha: f32,
+    pub color: u32,           // 0xRRGGBB
+    pub glow: Option<u32>,    // Shadow glow colour behind sprites
+    pub centered: bool,       // Text is centred on x
+    pub outlined: bool,       // Text gets a dark outline for readability
+}
+
+impl DrawCommand {
+    fn new(layer: Layer, kind: DrawKind, x: f32, y: f32, width: f32, height: f32) -> Self {
+        DrawCommand {
+            layer,
+            entity: layer.entity(),
+            kind,
+            x,
+            y,
+            width,
+            height,
+            flip_x: false,
+            alpha: 1.0,
+            color: 0xffffff,
+            glow: None,
+            centered: false,
+            outlined: false,
+        }
+    }
+
+    pub fn sprite(layer: Layer, sprite: Sprite, x: f32, y: f32, width: f32, height: f32) -> Self {
+        DrawCommand::new(layer, DrawKind::Sprite(sprite), x, y, width, height)
+    }
+
+    pub fn fill(layer: Layer, color: u32, x: f32, y: f32, width: f32, height: f32) -> Self {
+        DrawCommand {
+            color,
+            ..DrawCommand::new(layer, DrawKind::Fill, x, y, width, height)
+        }
+    }
+
+    // The string is supplied when it's pushed, see DrawList::push_text
+    pub fn text(layer: Layer, x: f32, y: f32, size: f32, color: u32) -> Self {
+        DrawCommand {
+            color,
+            ..DrawCommand::new(layer, DrawKind::Text(TextSpan::default()), x, y, 0.0, size)
+        }
+    }
+
+    pub fn with_entity(mut self, entity: EntityKind) -> Self {
+        self.entity = entity;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_glow(mut self, color: u32) -> Self {
+        self.glow = Some(color);
+        self
+    }
+
+    pub fn flipped(mut self) -> Self {
+        self.flip_x = true;
+        self
+    }
+
+    pub fn centered(mut self) -> Self {
+        self.centered = true;
+        self
+    }
+
+    pub fn outlined(mut self) -> Self {
+        self.outlined = true;
+        self
+    }
+}
+
+// A frame's draw commands and the strings their text commands point into.
+// Both keep their capacity between frames, so building a frame, HUD
+// numbers included, doesn't allocate once they've grown.
+#[derive(Debug, Default)]
+pub struct DrawList {
+    pub commands: Vec<DrawCommand>,
+    pub text: String,
+}
+
+impl DrawList {
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.text.clear();
+    }
+
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    // Writes `text` into the shared string and pushes `command` drawing it
+    pub fn push_text(&mut self, text: impl fmt::Display, mut command: DrawCommand) {
+        let start = self.text.len();
+        write!(self.text, "{}", text).expect("writing to a String can't fail");
+        command.kind = DrawKind::Text(TextSpan { start, len: self.text.len() - start });
+        self.commands.push(command);
+    }
+}
+
+const BACKGROUND_ALPHA: f32 = 0.75;
+// District tint laid over the page's animated sky
+const SKY_TINT_ALPHA: f32 = 0.25;
+const TRAIN_CAR_WIDTH: f32 = 40.0;
+const MISSING_SPRITE_COLOR: u32 = 0x808080;
+// How long the passenger's dialogue box stays up after boarding
+const DIALOGUE_SECONDS: f32 = 5.0;
+const DIRIGIBLE_COLOR: u32 = 0xc0392b;
+const FUEL_PAD_COLOR: u32 = 0x00ff66;
+const FUEL_BAR_X: f32 = 740.0;
+const FUEL_BAR_WIDTH: f32 = 150.0;
+
+fn building_sprite(building_type: &BuildingType) -> Sprite {
+    match building_type {
+        BuildingType::Wide => Sprite::WideBuilding,
+        BuildingType::Tall | BuildingType::Medium => Sprite::TallBuilding,
+    }
+}
+
+fn billboard_sprite(billboard_type: &BillboardType) -> Sprite {
+    match billboard_type {
+        BillboardType::FirstBreak => Sprite::FirstBreak,
+        BillboardType::SecondBreak => Sprite::SecondBreak,
+        BillboardType::Security => Sprite::Security,
+        BillboardType::Security2 => Sprite::Security2,
+        BillboardType::SharkMovie => Sprite::SharkMovie,
+    }
+}
+
+fn push_building(out: &mut DrawList, layer: Layer, building: &Building, offset: f32) {
+    out.push(
+        DrawCommand::sprite(
+            layer,
+            building_sprite(&building.building_type),
+            building.x + offset,
+            building.y,
+            building.width,
+            building.height,
+        )
+        .with_alpha(BACKGROUND_ALPHA),
+    );
+}
+
+fn push_vehicle(out: &mut DrawList, vehicle: &BackgroundVehicle, frame: u32, lag: f32) {
+    let layer = Layer::Traffic;
+    let (x, y) = rewind(vehicle.x, vehicle.y, vehicle.velocity(), lag);
+    match vehicle.vehicle_type {
+        VehicleType::Train => {
+            // Trains are drawn as a run of front/center/back cars
+            let cars = (vehicle.width / TRAIN_CAR_WIDTH).ceil() as u32;
+            for i in 0..cars {
+                let sprite = if i == 0 {
+                    Sprite::TrainFront
+                } else if i == cars - 1 {
+                    Sprite::TrainBack
+                } else {
+                    Sprite::TrainCenter
+                };
+                let offset = i as f32 * TRAIN_CAR_WIDTH;
+                let width = TRAIN_CAR_WIDTH.min(vehicle.width - offset);
+                out.push(DrawCommand::sprite(layer, sprite, x + offset, y, width, vehicle.height));
+            }
+        }
+        _ => {
+            // Vehicle art faces right and is mirrored for oncoming traffic
+            let sprite = match vehicle.vehicle_type {
+                VehicleType::Taxi => Sprite::Taxi,
+                VehicleType::Police | VehicleType::PoliceChase => Sprite::Police,
+                VehicleType::Civil => Sprite::Civil,
+                VehicleType::Delivery => Sprite::DeliveryTruck,
+                VehicleType::TrafficOrb => Sprite::Orb,
+                VehicleType::Train => Sprite::TrainCenter,
+            };
+            let mut command = DrawCommand::sprite(layer, sprite, x, y, vehicle.width, vehicle.height);
+            if !vehicle.moving_right {
+                command = command.flipped();
+            }
+            if matches!(vehicle.vehicle_type, VehicleType::TrafficOrb) {
+                command = command.with_glow(0x00ffff);
+            } else if matches!(vehicle.vehicle_type, VehicleType::PoliceChase) {
+                // Flashing red/blue lights, about three flips a second
+                let flash = (frame / 19).is_multiple_of(2);
+                command = command.with_glow(if flash { 0xff0000 } else { 0x0000ff });
+            } else if vehicle.avoiding {
+                command = command.with_glow(0xffff00);
+            }
+            out.push(command);
+        }
+    }
+
+    // Status indicators above the vehicle
+    let centre_x = x + vehicle.width / 2.0;
+    if vehicle.avoiding {
+        out.push_text("!", DrawCommand::text(layer, centre_x - 5.0, y - 5.0, 14.0, 0xffff00));
+    }
+    if vehicle.is_being_chased {
+        out.push_text("🏃", DrawCommand::text(layer, centre_x - 8.0, y - 8.0, 12.0, 0xff0000));
+    }
+    if vehicle.is_chasing {
+        out.push_text("👮", DrawCommand::text(layer, centre_x - 8.0, y - 8.0, 12.0, 0x0000ff));
+    }
+}
+
+fn push_obstacle(out: &mut DrawList, obstacle: &Obstacle, x: f32) {
+    let layer = Layer::Obstacles;
+    // Hitboxes are inset from their sprites
+    let inset = obstacle.inset;
+    match obstacle.sprite.as_deref().and_then(Sprite::from_key) {
+        Some(sprite) => out.push(DrawCommand::sprite(
+            layer,
+            sprite,
+            x - inset.left,
+            obstacle.y - inset.top,
+            obstacle.width + inset.left + inset.right,
+            obstacle.height + inset.top + inset.bottom,
+        )),
+        None => {
+            let color = match obstacle.obstacle_type {
+                ObstacleType::Dirigible => DIRIGIBLE_COLOR,
+                _ => MISSING_SPRITE_COLOR,
+            };
+            out.push(DrawCommand::fill(layer, color, x, obstacle.y, obstacle.width, obstacle.height));
+        }
+    }
+    // Fuel pads get a marked landing strip on the roof
+    if obstacle.has(ObstacleBehavior::Refuel) {
+        out.push(DrawCommand::fill(layer, FUEL_PAD_COLOR, x + 8.0, obstacle.y, obstacle.width - 16.0, 4.0));
+        out.push_text("⛽", DrawCommand::text(layer, x + obstacle.width / 2.0, obstacle.y - 6.0, 16.0, FUEL_PAD_COLOR).centered());
+    }
+}
+
+// Waiting passengers and the active fare's destination, marked above their rooftops
+fn push_fare_markers(out: &mut DrawList, state: &GameState, scroll_lag: f32) {
+    let layer = Layer::Obstacles;
+    let destination_id = state.fares.active.as_ref().and_then(|fare| fare.destination_id);
+    for obstacle in &state.obstacles {
+        let centre_x = obstacle.x + scroll_lag + obstacle.width / 2.0;
+        if state.fares.is_waiting_at(obstacle.id) {
+            let marker = DrawCommand::text(layer, centre_x, obstacle.y - 8.0, 16.0, 0xffffff).with_entity(EntityKind::FareMarker);
+            out.push_text("🙋", marker.centered());
+        }
+        if destination_id == Some(obstacle.id) {
+            let marker = DrawCommand::text(layer, centre_x, obstacle.y - 8.0, 18.0, 0x00ff66).with_entity(EntityKind::FareMarker);
+            out.push_text("▼", marker.centered().outlined());
+        }
+    }
+}
+
+fn pickup_style(kind: PickupKind) -> (&'static str, u32) {
+    match kind {
+        PickupKind::Repair => ("🔧", 0x00ff00),
+        PickupKind::Shield => ("🛡", 0x00ffff),
+        PickupKind::ScoreBonus => ("💰", 0xffd700),
+        PickupKind::Magnet => ("🧲", 0xff00ff),
+    }
+}
+
+fn push_pickup(out: &mut DrawList, pickup: &Pickup, lag: f32) {
+    let (glyph, color) = pickup_style(pickup.kind);
+    let (x, y) = rewind(pickup.x, pickup.y, (pickup.velocity_x, pickup.velocity_y), lag);
+    out.push_text(
+        glyph,
+        DrawCommand::text(Layer::Pickups, x + pickup.width / 2.0, y + pickup.height, pickup.height, color)
+            .centered()
+            .with_glow(color),
+    );
+}
+
+fn push_hud(out: &mut DrawList, state: &GameState) {
+    let layer = Layer::Hud;
+    let (canvas_width, canvas_height) = state.canvas_size();
+
+    out.push_text(format_args!("Score: {}", state.score), DrawCommand::text(layer, 10.0, 30.0, 20.0, 0xffffff).outlined());
+    out.push_text(format_args!("Level {}", state.difficulty.level), DrawCommand::text(layer, canvas_width / 2.0, 30.0, 20.0, 0xffffff).centered().outlined());
+
+    // Color-coded damage indicator like original game
+    let damage = state.player.damage;
+    let integrity_color = if damage < 2.0 {
+        0x00ff00 // Green - healthy
+    } else if damage < 6.0 {
+        0xdaa520 // Goldenrod - damaged
+    } else {
+        0xff0000 // Red - critical
+    };
+    out.push_text("Structural Integrity:", DrawCommand::text(layer, 10.0, 60.0, 20.0, 0xffffff).outlined());
+    out.push_text((10.0 - damage).floor(), DrawCommand::text(layer, 200.0, 60.0, 20.0, integrity_color).outlined());
+
+    // Active power-ups with seconds left
+    let power_ups = &state.pickups.power_ups;
+    let mut power_up_x = 10.0;
+    for (kind, seconds) in [(PickupKind::Shield, power_ups.shield), (PickupKind::Magnet, power_ups.magnet)] {
+        if seconds > 0.0 {
+            let (glyph, color) = pickup_style(kind);
+            out.push_text(format_args!("{} {}s", glyph, seconds.ceil()), DrawCommand::text(layer, power_up_x, 120.0, 16.0, color).outlined());
+            power_up_x += 70.0;
+        }
+    }
+
+    // Fuel gauge, top right
+    let fuel = state.player.fuel / state.player.fuel_capacity;
+    let fuel_color = if fuel < LOW_FUEL { 0xff0000 } else { FUEL_PAD_COLOR };
+    out.push_text("Fuel", DrawCommand::text(layer, FUEL_BAR_X - 48.0, 30.0, 18.0, 0xffffff).outlined());
+    out.push(DrawCommand::fill(layer, 0x000000, FUEL_BAR_X, 16.0, FUEL_BAR_WIDTH, 16.0).with_alpha(0.6));
+    out.push(DrawCommand::fill(layer, fuel_color, FUEL_BAR_X + 2.0, 18.0, (FUEL_BAR_WIDTH - 4.0) * fuel, 12.0));
+    if state.is_refueling() {
+        out.push_text("Refueling", DrawCommand::text(layer, FUEL_BAR_X + FUEL_BAR_WIDTH / 2.0, 50.0, 14.0, FUEL_PAD_COLOR).centered().outlined());
+    } else if state.player.is_out_of_fuel() {
+        out.push_text("OUT OF FUEL", DrawCommand::text(layer, FUEL_BAR_X + FUEL_BAR_WIDTH / 2.0, 50.0, 14.0, 0xff0000).centered().outlined());
+    }
+
+    if let Some(fare) = &state.fares.active {
+        let status = if fare.destination_id.is_some() { "in sight - land on top" } else { "ahead" };
+        out.push_text(
+            format_args!("Passenger aboard: {} {}", fare.destination.name(), status),
+            DrawCommand::text(layer, 10.0, 90.0, 18.0, 0x00ff66).outlined(),
+        );
+    }
+
+    if let (Some(line), Some(fare)) = (state.current_dialogue(), &state.fares.active) {
+        if fare.elapsed < DIALOGUE_SECONDS {
+            // Starts right of the speaker icon in the bottom-left corner
+            let top = canvas_height - 46.0;
+            out.push(DrawCommand::fill(layer, 0x000000, 40.0, top, canvas_width - 50.0, 36.0).with_alpha(0.7));
+            out.push_text("🧑", DrawCommand::text(layer, 48.0, top + 25.0, 18.0, 0xffffff));
+            out.push_text(line, DrawCommand::text(layer, 76.0, top + 24.0, 16.0, 0xffffff));
+        }
+    }
+
+    if state.game_over {
+        let centre_x = canvas_width / 2.0;
+        let centre_y = canvas_height / 2.0;
+        out.push(DrawCommand::fill(layer, 0x000000, 0.0, 0.0, canvas_width, canvas_height).with_alpha(0.8));
+        out.push_text("GAME OVER", DrawCommand::text(layer, centre_x, centre_y, 48.0, 0xff0066).centered().outlined());
+        out.push_text(
+            format_args!("Final Score: {}", state.score),
+            DrawCommand::text(layer, centre_x, centre_y + 50.0, 24.0, 0xffffff).centered().outlined(),
+        );
+        let prompt = if state.is_recording() {
+            "Press R to restart, ESC for menu or B to save a replay"
+        } else {
+            "Press R to restart or ESC for menu"
+        };
+        out.push_text(prompt, DrawCommand::text(layer, centre_x, centre_y + 80.0, 18.0, 0xffffff).centered().outlined());
+    }
+}
+
+// Where something moving at `velocity` was `lag` seconds before the last step
+fn rewind(x: f32, y: f32, velocity: (f32, f32), lag: f32) -> (f32, f32) {
+    (x - velocity.0 * lag, y - velocity.1 * lag)
+}
+
+// Builds the frame's draw list back to front. `alpha` blends everything that
+// moves between the last two fixed steps: the player from its previous
+// position, the rest of the world backed off along its velocity.
+pub fn build_commands(state: &GameState, alpha: f32, out: &mut DrawList) {
+    out.clear();
+    let background = &state.background;
+    let lag = (1.0 - alpha) * FIXED_DT;
+    // Obstacles and billboards ride the foreground scroll
+    let scroll_lag = state.difficulty.scroll_speed * lag;
+    let (canvas_width, canvas_height) = state.canvas_size();
+
+    // The incoming district's tint fades in across the blend zone
+    let (sky, incoming) = background.district.sky();
+    out.push(DrawCommand::fill(Layer::Sky, sky, 0.0, 0.0, canvas_width, canvas_height).with_alpha(SKY_TINT_ALPHA));
+    if let Some((color, progress)) = incoming {
+        out.push(
+            DrawCommand::fill(Layer::Sky, color, 0.0, 0.0, canvas_width, canvas_height).with_alpha(SKY_TINT_ALPHA * progress),
+        );
+    }
+
+    for building in &background.distant_buildings {
+        push_building(out, Layer::DistantBuildings, building, background.distant_building_offset);
+    }
+    for building in &background.far_buildings {
+        push_building(out, Layer::FarBuildings, building, background.far_building_offset);
+    }
+    for vehicle in &background.traffic_vehicles {
+        push_vehicle(out, vehicle, state.frame, lag);
+    }
+    for billboard in &background.billboards {
+        out.push(DrawCommand::sprite(
+            Layer::Billboards,
+            billboard_sprite(&billboard.billboard_type),
+            billboard.x + scroll_lag,
+            billboard.y,
+            billboard.width,
+            billboard.height,
+        ));
+    }
+    for obstacle in &state.obstacles {
+        push_obstacle(out, obstacle, obstacle.x + scroll_lag);
+    }
+    push_fare_markers(out, state, scroll_lag);
+    for pickup in &state.pickups.items {
+        push_pickup(out, pickup, lag);
+    }
+
+    let player = &state.player;
+    out.push(DrawCommand::sprite(
+        Layer::Player,
+        Sprite::Taxi,
+        player.prev_x + (player.x - player.prev_x) * alpha,
+        player.prev_y + (player.y - player.prev_y) * alpha,
+        player.width,
+        player.height,
+    ));
+
+    push_hud(out, state);
+}
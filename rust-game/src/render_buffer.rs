@@ -0,0 +1,139 @@
+use crate::game_state::GameState;
+use crate::render::{self, DrawKind, DrawList};
+
+// Flat encoding of the frame's draw commands (see render.rs) that the
+// frontend reads straight out of linear memory. Every record is STRIDE floats:
+//   entity kind, sprite id | text offset | -1 for fills, x, y, width, height,
+//   flags, alpha, color (0xRRGGBB, exact in f32)
+// Entity kind is the render::EntityKind discriminant. Records are in draw
+// order, back to front. Text records point into text():
+// the second field is the string's byte offset and width its length in bytes.
+pub const STRIDE: usize = 9;
+
+pub const FLAG_FLIP_X: u32 = 1 << 0;
+pub const FLAG_GLOW: u32 = 1 << 1; // color is the glow colour (text is also drawn in it)
+pub const FLAG_TEXT: u32 = 1 << 2; // second field and width locate the string in text()
+pub const FLAG_FILL: u32 = 1 << 3;
+pub const FLAG_CENTERED: u32 = 1 << 4;
+pub const FLAG_OUTLINED: u32 = 1 << 5;
+
+#[derive(Debug, Default)]
+pub struct RenderBuffer {
+    list: DrawList,
+    data: Vec<f32>,
+}
+
+impl RenderBuffer {
+    pub fn new() -> Self {
+        RenderBuffer::default()
+    }
+
+    pub fn as_ptr(&self) -> *const f32 {
+        self.data.as_ptr()
+    }
+
+    // Length in floats, not records
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // UTF-8 for every text record, back to back
+    pub fn text(&self) -> &str {
+        &self.list.text
+    }
+
+    // Rebuilds the buffer from the current state. Everything keeps its
+    // capacity between frames so steady-state filling doesn't allocate.
+    pub fn fill(&mut self, state: &GameState, alpha: f32) {
+        render::build_commands(state, alpha, &mut self.list);
+        self.data.clear();
+
+        for command in &self.list.commands {
+            let mut width = command.width;
+            let mut flags = 0;
+            let mut color = command.color;
+            let index = match &command.kind {
+                DrawKind::Sprite(sprite) => sprite.id() as f32,
+                DrawKind::Fill => {
+                    flags |= FLAG_FILL;
+                    -1.0
+                }
+                DrawKind::Text(span) => {
+                    flags |= FLAG_TEXT;
+                    width = span.len as f32;
+                    span.start as f32
+                }
+            };
+            if command.flip_x {
+                flags |= FLAG_FLIP_X;
+            }
+            if let Some(glow) = command.glow {
+                flags |= FLAG_GLOW;
+                color = glow;
+            }
+            if command.centered {
+                flags |= FLAG_CENTERED;
+            }
+            if command.outlined {
+                flags |= FLAG_OUTLINED;
+            }
+
+            self.data.extend_from_slice(&[
+                command.entity as u32 as f32,
+                index,
+                command.x,
+                command.y,
+                width,
+                command.height,
+                flags as f32,
+                command.alpha,
+                color as f32,
+            ]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{EntityKind, Sprite};
+
+    fn flags(record: &[f32]) -> u32 {
+        record[6] as u32
+    }
+
+    #[test]
+    fn records_decode_back_to_their_commands() {
+        let state = GameState::new_with_seed(7);
+        let mut buffer = RenderBuffer::new();
+        buffer.fill(&state, 1.0);
+        let records: Vec<&[f32]> = buffer.data.chunks(STRIDE).collect();
+        assert_eq!(buffer.len(), records.len() * STRIDE);
+        assert_eq!(records.len(), buffer.list.commands.len());
+        let (canvas_width, canvas_height) = state.canvas_size();
+
+        // The sky tint is a fill over the whole canvas, drawn first
+        let (sky, _) = state.background.district.sky();
+        assert_eq!(records[0][..2], [EntityKind::Sky as u32 as f32, -1.0]);
+        assert_eq!(records[0][2..6], [0.0, 0.0, canvas_width, canvas_height]);
+        assert_eq!(flags(records[0]), FLAG_FILL);
+        assert_eq!(records[0][8], sky as f32);
+
+        let player = &state.player;
+        let cab = records.iter().find(|r| r[0] == EntityKind::Player as u32 as f32).expect("cab");
+        assert_eq!(cab[1], Sprite::Taxi.id() as f32);
+        assert_eq!(cab[2..6], [player.x, player.y, player.width, player.height]);
+        assert_eq!((flags(cab), cab[7]), (0, 1.0));
+
+        // The first HUD string is the score; its span is a byte range in text()
+        let score = records.iter().find(|r| r[0] == EntityKind::Hud as u32 as f32 && flags(r) & FLAG_TEXT != 0).expect("score");
+        assert_eq!(flags(score), FLAG_TEXT | FLAG_OUTLINED);
+        let (start, len) = (score[1] as usize, score[4] as usize);
+        assert_eq!(&buffer.text()[start..start + len], "Score: 0");
+        assert_eq!((score[2], score[3], score[5], score[8]), (10.0, 30.0, 20.0, 0xffffff as f32));
+    }
+}
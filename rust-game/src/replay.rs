@@ -0,0 +1,388 @@
+use crate::input::{quantize_axis, Action, Axis, InputSource, InputState};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Binary layout (little endian):
+//   magic "BSCR" | format version u16 | build version (u8 len + utf8)
+//   | seed u64 | end frame u32 | event count u32
+//   | events: frame delta (LEB128 varint)
+//             + packed byte (control | source << 3 | pressed << 7)
+//             + i8 value (axis controls only, value * 127)
+const MAGIC: &[u8; 4] = b"BSCR";
+pub const FORMAT_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputChange {
+    Action(Action, bool),
+    Axis(Axis, f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InputEvent {
+    pub frame: u32, // Applied before the step that advances past this frame
+    pub source: InputSource,
+    pub change: InputChange,
+}
+
+impl InputChange {
+    pub fn apply(&self, source: InputSource, input: &mut InputState) {
+        match *self {
+            InputChange::Action(action, pressed) => input.set_action(source, action, pressed),
+            InputChange::Axis(axis, value) => input.set_axis(source, axis, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub build_version: String,
+    pub seed: u64,
+    pub end_frame: u32,
+    pub events: Vec<InputEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    BadMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+    InvalidEvent(u8),
+    InvalidBuildVersion,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::BadMagic => write!(f, "not a Bad Sky Cab replay"),
+            ReplayError::UnsupportedVersion(version) => write!(f, "unsupported replay format version {}", version),
+            ReplayError::Truncated => write!(f, "replay data is truncated"),
+            ReplayError::InvalidEvent(byte) => write!(f, "invalid input event byte {:#04x}", byte),
+            ReplayError::InvalidBuildVersion => write!(f, "replay build version is not valid utf-8"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+fn control_code(change: &InputChange) -> u8 {
+    match change {
+        InputChange::Action(Action::Right, _) => 0,
+        InputChange::Action(Action::Left, _) => 1,
+        InputChange::Action(Action::Up, _) => 2,
+        InputChange::Action(Action::Down, _) => 3,
+        InputChange::Axis(Axis::Horizontal, _) => 4,
+        InputChange::Axis(Axis::Vertical, _) => 5,
+    }
+}
+
+fn source_code(source: InputSource) -> u8 {
+    match source {
+        InputSource::Keyboard => 0,
+        InputSource::Gamepad => 1,
+        InputSource::Touch => 2,
+    }
+}
+
+fn source_from_code(code: u8) -> Option<InputSource> {
+    match code {
+        0 => Some(InputSource::Keyboard),
+        1 => Some(InputSource::Gamepad),
+        2 => Some(InputSource::Touch),
+        _ => None,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        let end = self.pos.checked_add(len).ok_or(ReplayError::Truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(ReplayError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ReplayError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReplayError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn varint(&mut self) -> Result<u32, ReplayError> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Truncated)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+impl Replay {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + self.events.len() * 2);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        let build = &self.build_version.as_bytes()[..self.build_version.len().min(255)];
+        out.push(build.len() as u8);
+        out.extend_from_slice(build);
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&self.end_frame.to_le_bytes());
+        out.extend_from_slice(&(self.events.len() as u32).to_le_bytes());
+
+        let mut last_frame = 0;
+        for event in &self.events {
+            write_varint(&mut out, event.frame - last_frame);
+            let packed = control_code(&event.change) | source_code(event.source) << 3;
+            match event.change {
+                InputChange::Action(_, pressed) => out.push(packed | (pressed as u8) << 7),
+                InputChange::Axis(_, value) => {
+                    out.push(packed);
+                    out.push((quantize_axis(value) * 127.0) as i8 as u8);
+                }
+            }
+            last_frame = event.frame;
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Replay, ReplayError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = reader.u16()?;
+        if version != FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let build_len = reader.u8()? as usize;
+        let build_version = String::from_utf8(reader.take(build_len)?.to_vec())
+            .map_err(|_| ReplayError::InvalidBuildVersion)?;
+        let seed = reader.u64()?;
+        let end_frame = reader.u32()?;
+        let count = reader.u32()?;
+
+        // Don't trust the count for preallocation, each event is at least 2 bytes
+        let mut events = Vec::with_capacity((count as usize).min(bytes.len() / 2));
+        let mut frame = 0u32;
+        for _ in 0..count {
+            frame = frame.checked_add(reader.varint()?).ok_or(ReplayError::Truncated)?;
+            let packed = reader.u8()?;
+            let source = source_from_code((packed >> 3) & 0x0f).ok_or(ReplayError::InvalidEvent(packed))?;
+            let pressed = packed & 0x80 != 0;
+            let change = match packed & 0x07 {
+                0 => InputChange::Action(Action::Right, pressed),
+                1 => InputChange::Action(Action::Left, pressed),
+                2 => InputChange::Action(Action::Up, pressed),
+                3 => InputChange::Action(Action::Down, pressed),
+                4 => InputChange::Axis(Axis::Horizontal, reader.u8()? as i8 as f32 / 127.0),
+                5 => InputChange::Axis(Axis::Vertical, reader.u8()? as i8 as f32 / 127.0),
+                _ => return Err(ReplayError::InvalidEvent(packed)),
+            };
+            events.push(InputEvent { frame, source, change });
+        }
+
+        Ok(Replay { build_version, seed, end_frame, events })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+// Logs input changes against the simulation frame they happened on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayRecorder {
+    replay: Replay,
+    last_input: InputState,
+}
+
+impl ReplayRecorder {
+    pub fn new(seed: u64) -> Self {
+        ReplayRecorder {
+            replay: Replay {
+                build_version: env!("CARGO_PKG_VERSION").to_string(),
+                seed,
+                end_frame: 0,
+                events: Vec::new(),
+            },
+            last_input: InputState::new(),
+        }
+    }
+
+    pub fn record(&mut self, frame: u32, source: InputSource, change: InputChange) {
+        // Key repeat and gamepad polling resend the same state over and
+        // over, only keep actual changes
+        let mut input = self.last_input.clone();
+        change.apply(source, &mut input);
+        if input == self.last_input {
+            return;
+        }
+        self.last_input = input;
+        self.replay.events.push(InputEvent { frame, source, change });
+    }
+
+    pub fn finish(&self, end_frame: u32) -> Replay {
+        let mut replay = self.replay.clone();
+        replay.end_frame = end_frame;
+        replay
+    }
+}
+
+// Feeds recorded events back into the input state at the matching frames
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayer { replay, cursor: 0 }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.replay.seed
+    }
+
+    pub fn feed(&mut self, frame: u32, input: &mut InputState) {
+        while let Some(event) = self.replay.events.get(self.cursor) {
+            if event.frame > frame {
+                break;
+            }
+            event.change.apply(event.source, input);
+            self.cursor += 1;
+        }
+    }
+
+    // Events logged on the last frame would only affect steps after the
+    // recording ended, so they don't hold playback open
+    pub fn is_finished(&self, frame: u32) -> bool {
+        frame >= self.replay.end_frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Replay {
+        let event = |frame, source, change| InputEvent { frame, source, change };
+        Replay {
+            build_version: "1.2.3".to_string(),
+            seed: 0xdead_beef_cafe,
+            end_frame: 900,
+            events: vec![
+                event(0, InputSource::Keyboard, InputChange::Action(Action::Up, true)),
+                event(3, InputSource::Keyboard, InputChange::Action(Action::Up, false)),
+                // A gap of more than 127 frames takes a multi-byte varint
+                event(400, InputSource::Gamepad, InputChange::Axis(Axis::Horizontal, quantize_axis(0.5))),
+                event(400, InputSource::Gamepad, InputChange::Axis(Axis::Vertical, -1.0)),
+                event(401, InputSource::Touch, InputChange::Action(Action::Left, true)),
+                event(899, InputSource::Touch, InputChange::Axis(Axis::Horizontal, quantize_axis(-0.3))),
+            ],
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let replay = sample();
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay));
+    }
+
+    #[test]
+    fn every_axis_step_survives_encoding() {
+        let events = (-127..=127)
+            .map(|step| InputEvent {
+                frame: 0,
+                source: InputSource::Gamepad,
+                change: InputChange::Axis(Axis::Vertical, quantize_axis(step as f32 / 127.0)),
+            })
+            .collect();
+        let replay = Replay { events, ..sample() };
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay));
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let bytes = sample().to_bytes();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(Replay::from_bytes(&bad_magic), Err(ReplayError::BadMagic));
+
+        for version in [0, FORMAT_VERSION + 1] {
+            let mut unsupported = bytes.clone();
+            unsupported[4..6].copy_from_slice(&version.to_le_bytes());
+            assert_eq!(Replay::from_bytes(&unsupported), Err(ReplayError::UnsupportedVersion(version)));
+        }
+
+        for len in [0, 5, 20, bytes.len() - 1] {
+            assert_eq!(Replay::from_bytes(&bytes[..len]), Err(ReplayError::Truncated));
+        }
+
+        // Control code 6 doesn't exist
+        let mut invalid = Replay { events: Vec::new(), ..sample() }.to_bytes();
+        let count_at = invalid.len() - 4;
+        invalid[count_at..].copy_from_slice(&1u32.to_le_bytes());
+        invalid.extend_from_slice(&[0, 6]);
+        assert_eq!(Replay::from_bytes(&invalid), Err(ReplayError::InvalidEvent(6)));
+    }
+
+    #[test]
+    fn recorder_keeps_only_changes() {
+        let mut recorder = ReplayRecorder::new(7);
+        recorder.record(0, InputSource::Keyboard, InputChange::Action(Action::Up, true));
+        recorder.record(1, InputSource::Keyboard, InputChange::Action(Action::Up, true));
+        recorder.record(2, InputSource::Gamepad, InputChange::Axis(Axis::Vertical, 0.0));
+        recorder.record(5, InputSource::Keyboard, InputChange::Action(Action::Up, false));
+
+        let replay = recorder.finish(10);
+        assert_eq!(replay.seed, 7);
+        assert_eq!(replay.end_frame, 10);
+        let frames: Vec<u32> = replay.events.iter().map(|event| event.frame).collect();
+        assert_eq!(frames, [0, 5]);
+    }
+
+    #[test]
+    fn player_feeds_events_up_to_the_frame() {
+        let mut player = ReplayPlayer::new(sample());
+        let mut input = InputState::new();
+        player.feed(2, &mut input);
+        assert_eq!(player.cursor, 1);
+        player.feed(400, &mut input);
+        assert_eq!(player.cursor, 4);
+        assert!(!player.is_finished(400));
+        player.feed(899, &mut input);
+        assert!(!player.is_finished(899));
+        assert!(player.is_finished(900));
+    }
+}
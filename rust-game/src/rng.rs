@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+// SplitMix64 - tiny, fast and good enough for gameplay randomness.
+// The whole generator is a single u64, so it serializes with the game state
+// and two runs started from the same seed produce identical worlds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Drop-in replacement for Math::random(): uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    // Index picked in proportion to its weight; always draws exactly one
+    // number. Zero weights are never picked, unless they all are, when the
+    // pick is even. `weights` must not be empty.
+    pub fn weighted_index(&mut self, weights: &[f32]) -> usize {
+        let roll = self.next_f64();
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return ((roll * weights.len() as f64) as usize).min(weights.len() - 1);
+        }
+        let mut roll = roll as f32 * total;
+        for (i, weight) in weights.iter().enumerate() {
+            if roll < *weight {
+                return i;
+            }
+            roll -= weight;
+        }
+        // Float rounding can leave the roll just past the last weight
+        weights.iter().rposition(|weight| *weight > 0.0).expect("total weight is positive")
+    }
+}
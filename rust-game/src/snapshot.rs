@@ -0,0 +1,121 @@
+use crate::game_state::GameState;
+use serde_json::Value;
+use std::fmt;
+
+// Layout: magic "BSCS" | format version u16 (little endian) | JSON body.
+// The body is migrated as loose JSON before being deserialized, so older
+// snapshots keep loading after GameState gains or renames fields.
+const MAGIC: &[u8; 4] = b"BSCS";
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+// MIGRATIONS[n] upgrades a version n + 1 body to version n + 2. Only formats
+// that shipped in a release need one; bump SNAPSHOT_VERSION and add an entry
+// here when a released format changes.
+type Migration = fn(&mut Value);
+const MIGRATIONS: &[Migration] = &[];
+const _: () = assert!(MIGRATIONS.len() == SNAPSHOT_VERSION as usize - 1, "every older snapshot version needs a migration");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    BadMagic,
+    Truncated,
+    UnsupportedVersion(u16),
+    Corrupt(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not a Bad Sky Cab snapshot"),
+            SnapshotError::Truncated => write!(f, "snapshot data is truncated"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {}", version),
+            SnapshotError::Corrupt(reason) => write!(f, "corrupt snapshot: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+pub fn save(state: &GameState) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+    // GameState only holds plain data, serializing it can't fail
+    serde_json::to_writer(&mut out, state).expect("game state serializes");
+    out
+}
+
+pub fn load(bytes: &[u8]) -> Result<GameState, SnapshotError> {
+    if bytes.len() < 6 {
+        return Err(SnapshotError::Truncated);
+    }
+    if &bytes[..4] != MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version == 0 || version > SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    let mut body: Value = serde_json::from_slice(&bytes[6..])
+        .map_err(|e| SnapshotError::Corrupt(e.to_string()))?;
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        migrate(&mut body);
+    }
+    serde_json::from_value(body).map_err(|e| SnapshotError::Corrupt(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{Action, InputSource};
+
+    fn played(frames: u32) -> GameState {
+        let mut state = GameState::new_with_seed(3);
+        state.start_recording();
+        state.set_action(InputSource::Keyboard, Action::Up, true);
+        for _ in 0..frames {
+            state.step();
+        }
+        state
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = played(120);
+        let bytes = save(&state);
+        assert_eq!(save(&load(&bytes).expect("loads")), bytes);
+    }
+
+    #[test]
+    fn loaded_game_carries_on_the_same() {
+        let mut original = played(120);
+        let mut loaded = GameState::new_with_seed(0);
+        loaded.load_snapshot(&original.save_snapshot()).expect("loads");
+        for _ in 0..240 {
+            original.step();
+            loaded.step();
+        }
+        assert_eq!(loaded.save_snapshot(), original.save_snapshot());
+        assert!(loaded.is_recording());
+    }
+
+    #[test]
+    fn rejects_bad_snapshots() {
+        let bytes = save(&played(10));
+
+        assert_eq!(load(&bytes[..5]).err(), Some(SnapshotError::Truncated));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[..4].copy_from_slice(b"BSCR");
+        assert_eq!(load(&bad_magic).err(), Some(SnapshotError::BadMagic));
+
+        for version in [0, SNAPSHOT_VERSION + 1] {
+            let mut unsupported = bytes.clone();
+            unsupported[4..6].copy_from_slice(&version.to_le_bytes());
+            assert_eq!(load(&unsupported).err(), Some(SnapshotError::UnsupportedVersion(version)));
+        }
+
+        assert!(matches!(load(&bytes[..bytes.len() - 1]), Err(SnapshotError::Corrupt(_))));
+    }
+}
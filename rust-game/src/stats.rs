@@ -0,0 +1,28 @@
+use crate::entities::ObstacleType;
+use crate::background::VehicleType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Running tallies of what the player ran into, for tuning and batch sims.
+// Only hits count: contacts that dealt no damage or came during the
+// collision cooldown are left out, the same for every kind of collider.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollisionStats {
+    pub obstacles: BTreeMap<ObstacleType, u32>,
+    pub vehicles: BTreeMap<VehicleType, u32>,
+    pub billboards: u32,
+}
+
+impl CollisionStats {
+    pub fn record_obstacle(&mut self, obstacle_type: &ObstacleType) {
+        *self.obstacles.entry(obstacle_type.clone()).or_insert(0) += 1;
+    }
+
+    pub fn record_vehicle(&mut self, vehicle_type: &VehicleType) {
+        *self.vehicles.entry(vehicle_type.clone()).or_insert(0) += 1;
+    }
+
+    pub fn record_billboard(&mut self) {
+        self.billboards += 1;
+    }
+}
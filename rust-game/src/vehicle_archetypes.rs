@@ -0,0 +1,308 @@
+use crate::background::VehicleType;
+use crate::obstacle_archetypes::check_body;
+use crate::rng::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Traffic lanes and the vehicles that may fill each role in them
+const BUILTIN_REGISTRY: &str = include_str!("../data/vehicles.json");
+
+// Where a vehicle spawns and what it does there
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrafficRole {
+    Right,   // Top lane, heading right
+    Left,    // Bottom lane, heading left
+    Fleeing, // Front of a police chase in the top lane
+    Chasing, // Police car behind a fleeing vehicle
+}
+
+impl TrafficRole {
+    pub const ALL: [TrafficRole; 4] = [TrafficRole::Right, TrafficRole::Left, TrafficRole::Fleeing, TrafficRole::Chasing];
+
+    // Which way the spawner sends this role: everything but left-bound
+    // traffic enters from the left edge
+    pub fn direction(&self) -> Direction {
+        match self {
+            TrafficRole::Left => Direction::Left,
+            TrafficRole::Right | TrafficRole::Fleeing | TrafficRole::Chasing => Direction::Right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Right,
+    Left,
+}
+
+// A named traffic lane: where its vehicles' tops sit and which way it flows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lane {
+    pub name: String,
+    pub y: f32,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    Parse(String),
+    NotAllowed(VehicleType, TrafficRole),
+    InvalidArchetype(VehicleType, TrafficRole, &'static str), // What's wrong with it
+    EmptyRole(TrafficRole), // Nothing with a spawn weight to fill it
+    DuplicateLane(String),
+    UnknownLane(String),
+    WrongDirection(String, TrafficRole), // Lane flows against the role's direction
+    ChaserTooSlow(VehicleType), // Could never catch the fastest fleeing vehicle
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Parse(reason) => write!(f, "invalid vehicle definitions: {}", reason),
+            RegistryError::NotAllowed(vehicle_type, role) => {
+                write!(f, "{:?} vehicles can't spawn as {:?} traffic", vehicle_type, role)
+            }
+            RegistryError::InvalidArchetype(vehicle_type, role, reason) => {
+                write!(f, "{:?} vehicle archetype for {:?} traffic: {}", vehicle_type, role, reason)
+            }
+            RegistryError::EmptyRole(role) => write!(f, "no vehicle archetype can spawn as {:?} traffic", role),
+            RegistryError::DuplicateLane(name) => write!(f, "traffic lane `{}` is defined twice", name),
+            RegistryError::UnknownLane(name) => write!(f, "unknown traffic lane `{}`", name),
+            RegistryError::WrongDirection(lane, role) => {
+                write!(f, "traffic lane `{}` flows the wrong way for {:?} traffic", lane, role)
+            }
+            RegistryError::ChaserTooSlow(vehicle_type) => {
+                write!(f, "{:?} chasing archetype is slower than the fastest fleeing vehicle", vehicle_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+// Speed is base + up to speed_variance px/s. Chasing cars' speed is absolute
+// too, and its base has to beat every fleeing archetype's top speed so the
+// chase closes in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VehicleArchetype {
+    pub vehicle_type: VehicleType,
+    pub role: TrafficRole,
+    pub lane: String, // Name of one of the registry's lanes
+    pub width: f32,
+    pub height: f32,
+    pub damage: f32,
+    pub speed: f32,
+    pub speed_variance: f32,
+    pub spawn_weight: f32, // Relative to other archetypes in the same role
+}
+
+impl VehicleArchetype {
+    // Always draws exactly one number
+    pub fn roll_speed(&self, rng: &mut Rng) -> f32 {
+        self.speed + (rng.next_f64() * self.speed_variance as f64) as f32
+    }
+
+    fn top_speed(&self) -> f32 {
+        self.speed + self.speed_variance
+    }
+
+    fn validate(&self, registry: &VehicleRegistry) -> Result<(), RegistryError> {
+        let invalid = |reason| Err(RegistryError::InvalidArchetype(self.vehicle_type.clone(), self.role, reason));
+        let allowed = registry.allowed_roles.get(&self.vehicle_type).is_some_and(|roles| roles.contains(&self.role));
+        if !allowed {
+            return Err(RegistryError::NotAllowed(self.vehicle_type.clone(), self.role));
+        }
+        let lane = registry.lane(&self.lane).ok_or_else(|| RegistryError::UnknownLane(self.lane.clone()))?;
+        if lane.direction != self.role.direction() {
+            return Err(RegistryError::WrongDirection(self.lane.clone(), self.role));
+        }
+        check_body(self.width, self.height, self.damage, self.spawn_weight).or_else(invalid)?;
+        if !(self.speed >= 0.0 && self.speed_variance >= 0.0) {
+            return invalid("speed and speed_variance can't be negative");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VehicleRegistry {
+    pub lanes: Vec<Lane>,
+    // Roles each vehicle type may spawn in; types left out can't spawn at all
+    pub allowed_roles: BTreeMap<VehicleType, Vec<TrafficRole>>,
+    pub archetypes: Vec<VehicleArchetype>,
+}
+
+impl Default for VehicleRegistry {
+    fn default() -> Self {
+        VehicleRegistry::from_json(BUILTIN_REGISTRY).expect("built-in vehicle definitions are valid")
+    }
+}
+
+impl VehicleRegistry {
+    pub fn from_json(json: &str) -> Result<Self, RegistryError> {
+        let registry: VehicleRegistry = serde_json::from_str(json).map_err(|e| RegistryError::Parse(e.to_string()))?;
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    pub fn validate(&self) -> Result<(), RegistryError> {
+        for (i, lane) in self.lanes.iter().enumerate() {
+            if self.lanes[..i].iter().any(|l| l.name == lane.name) {
+                return Err(RegistryError::DuplicateLane(lane.name.clone()));
+            }
+        }
+        for archetype in &self.archetypes {
+            archetype.validate(self)?;
+        }
+        for role in TrafficRole::ALL {
+            if !self.archetypes.iter().any(|a| a.role == role && a.spawn_weight > 0.0) {
+                return Err(RegistryError::EmptyRole(role));
+            }
+        }
+        let fleeing_top_speed = self
+            .archetypes
+            .iter()
+            .filter(|a| a.role == TrafficRole::Fleeing)
+            .map(VehicleArchetype::top_speed)
+            .fold(0.0, f32::max);
+        if let Some(chaser) = self.archetypes.iter().find(|a| a.role == TrafficRole::Chasing && a.speed <= fleeing_top_speed) {
+            return Err(RegistryError::ChaserTooSlow(chaser.vehicle_type.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn lane(&self, name: &str) -> Option<&Lane> {
+        self.lanes.iter().find(|lane| lane.name == name)
+    }
+
+    // Validation guarantees every archetype's lane exists
+    pub fn lane_of(&self, archetype: &VehicleArchetype) -> &Lane {
+        self.lane(&archetype.lane).expect("archetype lanes are validated")
+    }
+
+    // Weighted pick among the archetypes for `role`; validation guarantees one
+    pub fn random(&self, role: TrafficRole, rng: &mut Rng) -> &VehicleArchetype {
+        let candidates: Vec<_> = self.archetypes.iter().filter(|a| a.role == role && a.spawn_weight > 0.0).collect();
+        let weights: Vec<f32> = candidates.iter().map(|a| a.spawn_weight).collect();
+        candidates[rng.weighted_index(&weights)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::BackgroundVehicle;
+
+    // A small registry unlike the built-in one, so nothing here leans on
+    // the shipped data: lanes at other heights and only taxis for traffic
+    const CUSTOM: &str = r#"{
+        "lanes": [
+            { "name": "high", "y": 30, "direction": "Right" },
+            { "name": "low", "y": 140, "direction": "Left" }
+        ],
+        "allowed_roles": {
+            "Taxi": ["Right", "Left", "Fleeing"],
+            "PoliceChase": ["Chasing"]
+        },
+        "archetypes": [
+            { "vehicle_type": "Taxi", "role": "Right", "lane": "high", "width": 30, "height": 15, "damage": 1, "speed": 100, "speed_variance": 0, "spawn_weight": 1 },
+            { "vehicle_type": "Taxi", "role": "Left", "lane": "low", "width": 32, "height": 16, "damage": 2, "speed": 80, "speed_variance": 20, "spawn_weight": 1 },
+            { "vehicle_type": "Taxi", "role": "Fleeing", "lane": "high", "width": 36, "height": 18, "damage": 3, "speed": 200, "speed_variance": 50, "spawn_weight": 1 },
+            { "vehicle_type": "PoliceChase", "role": "Chasing", "lane": "high", "width": 40, "height": 20, "damage": 6, "speed": 300, "speed_variance": 0, "spawn_weight": 1 }
+        ]
+    }"#;
+
+    fn custom(edit: impl FnOnce(&mut VehicleRegistry)) -> Result<(), RegistryError> {
+        let mut registry = VehicleRegistry::from_json(CUSTOM).expect("valid");
+        edit(&mut registry);
+        registry.validate()
+    }
+
+    fn find(registry: &mut VehicleRegistry, role: TrafficRole) -> &mut VehicleArchetype {
+        registry.archetypes.iter_mut().find(|a| a.role == role).expect("one per role")
+    }
+
+    #[test]
+    fn roles_come_from_allowed_roles() {
+        let result = custom(|r| find(r, TrafficRole::Chasing).vehicle_type = VehicleType::Taxi);
+        assert_eq!(result, Err(RegistryError::NotAllowed(VehicleType::Taxi, TrafficRole::Chasing)));
+
+        // ...until the data says otherwise
+        let result = custom(|r| {
+            r.allowed_roles.insert(VehicleType::Taxi, TrafficRole::ALL.to_vec());
+            find(r, TrafficRole::Chasing).vehicle_type = VehicleType::Taxi;
+        });
+        assert_eq!(result, Ok(()));
+
+        // Types missing from the table can't spawn anywhere
+        let result = custom(|r| find(r, TrafficRole::Right).vehicle_type = VehicleType::Train);
+        assert_eq!(result, Err(RegistryError::NotAllowed(VehicleType::Train, TrafficRole::Right)));
+    }
+
+    #[test]
+    fn lanes_must_exist_once_and_flow_with_the_role() {
+        let result = custom(|r| find(r, TrafficRole::Right).lane = "top".to_string());
+        assert_eq!(result, Err(RegistryError::UnknownLane("top".to_string())));
+
+        let result = custom(|r| find(r, TrafficRole::Fleeing).lane = "low".to_string());
+        assert_eq!(result, Err(RegistryError::WrongDirection("low".to_string(), TrafficRole::Fleeing)));
+
+        let result = custom(|r| {
+            let copy = r.lanes[1].clone();
+            r.lanes.push(copy);
+        });
+        assert_eq!(result, Err(RegistryError::DuplicateLane("low".to_string())));
+    }
+
+    #[test]
+    fn rejects_negative_speeds() {
+        let invalid = Err(RegistryError::InvalidArchetype(
+            VehicleType::Taxi,
+            TrafficRole::Left,
+            "speed and speed_variance can't be negative",
+        ));
+        assert_eq!(custom(|r| find(r, TrafficRole::Left).speed = -1.0), invalid);
+        assert_eq!(custom(|r| find(r, TrafficRole::Left).speed_variance = f32::NAN), invalid);
+    }
+
+    #[test]
+    fn every_role_needs_something_to_spawn() {
+        for role in TrafficRole::ALL {
+            let result = custom(|r| find(r, role).spawn_weight = 0.0);
+            assert_eq!(result, Err(RegistryError::EmptyRole(role)));
+        }
+    }
+
+    #[test]
+    fn chasers_must_outrun_every_fleeing_vehicle() {
+        // The fleeing taxi tops out at 250
+        let result = custom(|r| find(r, TrafficRole::Chasing).speed = 250.0);
+        assert_eq!(result, Err(RegistryError::ChaserTooSlow(VehicleType::PoliceChase)));
+
+        let result = custom(|r| find(r, TrafficRole::Fleeing).speed_variance = 100.0);
+        assert_eq!(result, Err(RegistryError::ChaserTooSlow(VehicleType::PoliceChase)));
+    }
+
+    #[test]
+    fn spawns_follow_the_registry() {
+        let registry = VehicleRegistry::from_json(CUSTOM).expect("valid");
+        let mut rng = Rng::new(21);
+
+        let right = BackgroundVehicle::new_right_moving(-40.0, &registry, &mut rng);
+        assert_eq!((right.y, right.width, right.height, right.speed), (30.0, 30.0, 15.0, 100.0));
+        assert!(right.moving_right);
+
+        for _ in 0..20 {
+            let left = BackgroundVehicle::new_left_moving(400.0, &registry, &mut rng);
+            assert_eq!((left.y, left.damage), (140.0, 2.0));
+            assert!(!left.moving_right);
+            assert!((80.0..=100.0).contains(&left.speed));
+        }
+
+        let (target, police) = BackgroundVehicle::new_chase_pair(-40.0, &registry, &mut rng);
+        assert_eq!((target.vehicle_type, target.y), (VehicleType::Taxi, 30.0));
+        assert_eq!((police.vehicle_type, police.speed), (VehicleType::PoliceChase, 300.0));
+        assert!(target.is_being_chased && police.is_chasing);
+    }
+}
@@ -0,0 +1,238 @@
+use wasm_bindgen::prelude::*;
+
+use crate::dialogue::DialogueTables;
+use crate::game_state::GameState as InternalGameState;
+use crate::input::{Action, Axis, InputSource};
+use crate::obstacle_archetypes::ObstacleCatalog;
+use crate::render::Sprite;
+use crate::render_buffer::RenderBuffer;
+use crate::replay::Replay;
+use crate::vehicle_archetypes::VehicleRegistry;
+
+// Enable panic hooks for better error messages
+#[wasm_bindgen(start)]
+pub fn main() {
+    console_error_panic_hook::set_once();
+}
+
+// Seed for games started without an explicit one
+fn random_seed() -> u64 {
+    let high = (js_sys::Math::random() * u32::MAX as f64) as u64;
+    let low = (js_sys::Math::random() * u32::MAX as f64) as u64;
+    (high << 32) | low
+}
+
+#[wasm_bindgen]
+pub struct GameState {
+    state: InternalGameState,
+    render_buffer: RenderBuffer,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl GameState {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> GameState {
+        GameState {
+            state: InternalGameState::new_with_seed(random_seed()),
+            render_buffer: RenderBuffer::new(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn new_with_seed(seed: u64) -> GameState {
+        GameState {
+            state: InternalGameState::new_with_seed(seed),
+            render_buffer: RenderBuffer::new(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn get_seed(&self) -> u64 {
+        self.state.seed
+    }
+
+    // Advance by the real frame time in milliseconds; returns the render
+    // interpolation alpha between the previous and current step
+    #[wasm_bindgen]
+    pub fn update(&mut self, dt_ms: f64) -> f32 {
+        self.state.update(dt_ms)
+    }
+
+    // Fill the frame's draw commands into the flat render buffer. Read it with
+    // new Float32Array(memory.buffer, render_buffer_ptr(), render_buffer_len())
+    // right after filling - the view is invalidated if wasm memory grows.
+    #[wasm_bindgen]
+    pub fn fill_render_buffer(&mut self, alpha: f32) {
+        self.render_buffer.fill(&self.state, alpha);
+    }
+
+    #[wasm_bindgen]
+    pub fn render_buffer_ptr(&self) -> *const f32 {
+        self.render_buffer.as_ptr()
+    }
+
+    #[wasm_bindgen]
+    pub fn render_buffer_len(&self) -> usize {
+        self.render_buffer.len()
+    }
+
+    // UTF-8 bytes that text records point into. Read it with
+    // new Uint8Array(memory.buffer, render_text_ptr(), render_text_len())
+    // under the same rules as the render buffer.
+    #[wasm_bindgen]
+    pub fn render_text_ptr(&self) -> *const u8 {
+        self.render_buffer.text().as_ptr()
+    }
+
+    #[wasm_bindgen]
+    pub fn render_text_len(&self) -> usize {
+        self.render_buffer.text().len()
+    }
+
+    // Image keys (paths under assets/images, no extension) indexed by sprite id
+    #[wasm_bindgen]
+    pub fn render_sprite_keys() -> Vec<String> {
+        Sprite::ALL.iter().map(|sprite| sprite.key().to_string()).collect()
+    }
+
+    // Array of tagged event objects, e.g. { type: "HitByVehicle", vehicle_type: "Taxi", damage: 2 }
+    #[wasm_bindgen]
+    pub fn drain_events(&mut self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.state.drain_events()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn get_state(&self) -> String {
+        serde_json::to_string(&self.state).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    // What the current passenger said on boarding, if anyone is aboard
+    #[wasm_bindgen]
+    pub fn get_dialogue(&self) -> Option<String> {
+        self.state.current_dialogue().map(str::to_string)
+    }
+
+    // Replace the dialogue tables with JSON shaped like data/dialogue.json
+    #[wasm_bindgen]
+    pub fn set_dialogue_tables(&mut self, json: &str) -> Result<(), JsValue> {
+        let tables = DialogueTables::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.set_dialogue_tables(tables);
+        Ok(())
+    }
+
+    // Replace the obstacle definitions with JSON shaped like data/obstacles.json
+    #[wasm_bindgen]
+    pub fn set_obstacle_catalog(&mut self, json: &str) -> Result<(), JsValue> {
+        let catalog = ObstacleCatalog::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.set_obstacle_catalog(catalog);
+        Ok(())
+    }
+
+    // Replace the traffic definitions with JSON shaped like data/vehicles.json
+    #[wasm_bindgen]
+    pub fn set_vehicle_registry(&mut self, json: &str) -> Result<(), JsValue> {
+        let registry = VehicleRegistry::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.set_vehicle_registry(registry);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_action(&mut self, source: InputSource, action: Action, pressed: bool) {
+        self.state.set_action(source, action, pressed);
+    }
+
+    // Analog thrust in -1.0..1.0, e.g. from a gamepad stick
+    #[wasm_bindgen]
+    pub fn set_axis(&mut self, source: InputSource, axis: Axis, value: f32) {
+        self.state.set_axis(source, axis, value);
+    }
+
+    #[wasm_bindgen]
+    pub fn start_recording(&mut self) {
+        self.state.start_recording();
+    }
+
+    #[wasm_bindgen]
+    pub fn is_recording(&self) -> bool {
+        self.state.is_recording()
+    }
+
+    // Compact binary replay for attaching to bug reports
+    #[wasm_bindgen]
+    pub fn export_replay(&self) -> Result<Vec<u8>, JsValue> {
+        self.state
+            .export_replay()
+            .map(|replay| replay.to_bytes())
+            .ok_or_else(|| JsValue::from_str("not recording"))
+    }
+
+    #[wasm_bindgen]
+    pub fn export_replay_json(&self) -> Result<String, JsValue> {
+        self.state
+            .export_replay()
+            .map(|replay| replay.to_json())
+            .ok_or_else(|| JsValue::from_str("not recording"))
+    }
+
+    #[wasm_bindgen]
+    pub fn load_replay(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let replay = Replay::from_bytes(bytes).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state.load_replay(replay);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn is_replaying(&self) -> bool {
+        self.state.is_replaying()
+    }
+
+    // Versioned snapshot of the full simulation, for suspend/resume
+    #[wasm_bindgen]
+    pub fn save_snapshot(&self) -> Vec<u8> {
+        self.state.save_snapshot()
+    }
+
+    #[wasm_bindgen]
+    pub fn load_snapshot(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.state
+            .load_snapshot(bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    #[wasm_bindgen]
+    pub fn is_game_over(&self) -> bool {
+        self.state.game_over
+    }
+
+    #[wasm_bindgen]
+    pub fn get_score(&self) -> u32 {
+        self.state.score
+    }
+
+    #[wasm_bindgen]
+    pub fn get_damage(&self) -> f32 {
+        self.state.player.damage
+    }
+
+    // Remaining fuel as a fraction of a full tank
+    #[wasm_bindgen]
+    pub fn get_fuel(&self) -> f32 {
+        self.state.player.fuel / self.state.player.fuel_capacity
+    }
+
+    #[wasm_bindgen]
+    pub fn get_level(&self) -> u32 {
+        self.state.difficulty.level
+    }
+}